@@ -1,4 +1,5 @@
 use std::{fmt, io};
+use std::path::PathBuf;
 
 use egui::mutex::Mutex;
 use egui_extras::Size;
@@ -8,6 +9,7 @@ use once_cell::sync::Lazy;
 
 use crate::State;
 use crate::pane::{PaneId, Pane, init_panes};
+use crate::project;
 
 pub static STATUS: Lazy<Mutex<String>> = Lazy::new(|| 
     Mutex::new(String::from("")));
@@ -15,8 +17,20 @@ pub static STATUS: Lazy<Mutex<String>> = Lazy::new(||
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct App {
+    // Stored on its own as a versioned project, see `STATE_KEY`
+    #[serde(skip)]
     state: State,
 
+    project: Option<PathBuf>,
+    recent: Vec<PathBuf>,
+
+    // A previous session that couldn't be read, see `BACKUP_KEY`
+    #[serde(skip)]
+    backup: Option<String>,
+
+    #[serde(skip)]
+    prompt: Option<ProjectPrompt>,
+
     #[serde(skip)]
     panes: EnumMap<PaneId, Box<dyn Pane>>,
 
@@ -28,12 +42,34 @@ impl Default for App {
     fn default() -> Self {
         Self { 
             state: State::default(), 
+            project: None,
+            recent: Vec::new(),
+            backup: None,
+            prompt: None,
             panes: init_panes(),
             pane_state: EnumMap::default()
         }
     }
 }
 
+/// A path being entered for one of the project menu's actions
+enum ProjectPrompt {
+    Open(String),
+    SaveAs(String),
+    ExportBackup(String)
+}
+
+const MAX_RECENT: usize = 8;
+
+const STATE_KEY: &str = "state";
+const BACKUP_KEY: &str = "state-backup";
+
+fn report<T: fmt::Display>(message: T) {
+    let mut status = STATUS.lock();
+    status.clear();
+    status.push_str(format!("{}", message).as_str());
+}
+
 #[allow(dead_code)]
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Font {
@@ -125,18 +161,215 @@ impl App {
         }
         
         if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY)
+            let mut app: Self = eframe::get_value(storage, eframe::APP_KEY)
                 .unwrap_or_default();
+
+            app.backup = storage
+                .get_string(BACKUP_KEY)
+                .filter(|backup| !backup.is_empty());
+
+            // Sessions saved before projects were versioned kept `State` inside of `App`
+            let content = storage
+                .get_string(STATE_KEY)
+                .or_else(|| storage.get_string(eframe::APP_KEY));
+
+            if let Some(content) = content {
+                match project::from_str(&content) {
+                    Ok(state) => app.state = state,
+                    Err(error) => {
+                        let _ = app.backup.insert(content);
+
+                        report(format!("Unable to restore the previous session, it has been kept as a backup: {}", error));
+                    }
+                }
+            }
+
+            return app;
         }
 
         Default::default()
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl App {
+    fn remember(&mut self, path: PathBuf) {
+        self.recent.retain(|other| *other != path);
+        self.recent.insert(0, path.clone());
+        self.recent.truncate(MAX_RECENT);
+
+        let _ = self.project.insert(path);
+    }
+
+    fn replace_state(&mut self, state: State) {
+        self.state = state;
+        self.panes = init_panes();
+    }
+
+    fn open(&mut self, path: PathBuf) {
+        match project::load(&path) {
+            Ok(state) => {
+                self.replace_state(state);
+
+                report(format!("Opened {}", path.display()));
+
+                self.remember(path);
+            },
+            Err(error) => report(format!("Unable to open {}: {}", path.display(), error))
+        }
+    }
+
+    fn save_as(&mut self, mut path: PathBuf) {
+        if path.extension().is_none() {
+            path.set_extension(project::EXTENSION);
+        }
+
+        match project::save(&path, &self.state) {
+            Ok(()) => {
+                report(format!("Saved {}", path.display()));
+
+                self.remember(path);
+            },
+            Err(error) => report(format!("Unable to save {}: {}", path.display(), error))
+        }
+    }
+
+    /// The backup is written exactly as it was stored, so that nothing is lost
+    fn export_backup(&mut self, path: PathBuf) {
+        let backup = match &self.backup {
+            Some(backup) => backup,
+            None => return
+        };
+
+        match std::fs::write(&path, backup) {
+            Ok(()) => report(format!("Exported backup to {}", path.display())),
+            Err(error) => report(format!("Unable to export backup to {}: {}", path.display(), error))
+        }
+    }
+
+    fn project_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button("Project", |ui| {
+            if ui.button("New").clicked() {
+                self.replace_state(State::default());
+
+                let _ = self.project.take();
+
+                ui.close_menu();
+            }
+
+            if ui.button("Open...").clicked() {
+                let _ = self.prompt.insert(ProjectPrompt::Open(String::new()));
+
+                ui.close_menu();
+            }
+
+            if ui.button("Save").clicked() {
+                match self.project.clone() {
+                    Some(path) => self.save_as(path),
+                    None => {
+                        let _ = self.prompt.insert(ProjectPrompt::SaveAs(String::new()));
+                    }
+                }
+
+                ui.close_menu();
+            }
+
+            if ui.button("Save As...").clicked() {
+                let path = self.project
+                    .as_ref()
+                    .map(|path| format!("{}", path.display()))
+                    .unwrap_or_default();
+
+                let _ = self.prompt.insert(ProjectPrompt::SaveAs(path));
+
+                ui.close_menu();
+            }
+
+            if self.backup.is_some() {
+                ui.separator();
+
+                if ui.button("Export Backup...").clicked() {
+                    let _ = self.prompt.insert(ProjectPrompt::ExportBackup(String::new()));
+
+                    ui.close_menu();
+                }
+
+                if ui.button("Discard Backup").clicked() {
+                    let _ = self.backup.take();
+
+                    ui.close_menu();
+                }
+            }
+
+            ui.separator();
+
+            ui.add_enabled_ui(!self.recent.is_empty(), |ui| {
+                ui.menu_button("Recent", |ui| {
+                    for path in self.recent.clone() {
+                        if ui.button(format!("{}", path.display())).clicked() {
+                            self.open(path);
+
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+        });
+    }
+
+    fn show_prompt(&mut self, ctx: &egui::Context) {
+        let (title, path) = match &mut self.prompt {
+            Some(ProjectPrompt::Open(path)) => ("Open Project", path),
+            Some(ProjectPrompt::SaveAs(path)) => ("Save Project As", path),
+            Some(ProjectPrompt::ExportBackup(path)) => ("Export Backup", path),
+            None => return
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Path");
+
+                    let response = ui.text_edit_singleline(path);
+
+                    confirmed = response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                });
+
+                ui.horizontal(|ui| {
+                    confirmed |= ui.button("Confirm").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+
+        if cancelled {
+            let _ = self.prompt.take();
+        } else if confirmed {
+            match self.prompt.take() {
+                Some(ProjectPrompt::Open(path)) => self.open(PathBuf::from(path.trim())),
+                Some(ProjectPrompt::SaveAs(path)) => self.save_as(PathBuf::from(path.trim())),
+                Some(ProjectPrompt::ExportBackup(path)) => self.export_backup(PathBuf::from(path.trim())),
+                None => { /*  */ }
+            }
+        }
+    }
+}
+
 impl eframe::App for App {
     // Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, eframe::APP_KEY, self);
+
+        match project::to_string(&self.state) {
+            Ok(content) => storage.set_string(STATE_KEY, content),
+            Err(error) => log::error!("{}", error)
+        }
+
+        storage.set_string(BACKUP_KEY, self.backup.clone().unwrap_or_default());
     }
 
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
@@ -144,6 +377,9 @@ impl eframe::App for App {
 
         egui::TopBottomPanel::top("top-panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
+                #[cfg(not(target_arch = "wasm32"))]
+                self.project_menu(ui);
+
                 ui.menu_button("Dock", |ui| {
                     for (id, state) in self.pane_state.iter_mut() {
                         let title = self.panes[id].title(&self.state);
@@ -193,6 +429,9 @@ impl eframe::App for App {
                 });
         });
 
+        #[cfg(not(target_arch = "wasm32"))]
+        self.show_prompt(ctx);
+
         for (id, pane) in self.panes.iter_mut() {
             if !self.pane_state[id] {
                 pane.setup(&self.state, ctx).show(ctx, |ui| {
@@ -0,0 +1,276 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+use std::io::{self, BufRead, Write};
+use std::{env, fs, process};
+
+use petgraph::stable_graph::NodeIndex;
+
+use phonemenomicon::{project, State};
+use phonemenomicon::types::{parse_prosodic_word, prosodic_word_to_string};
+
+const USAGE: &str = "\
+Applies a project's sound changes to a list of words
+
+Usage: phonemenomicon-cli <PROJECT> <DIALECT PATH> [WORDS] [--format tsv|json]
+
+  <PROJECT>      A project saved from the editor
+  <DIALECT PATH> Dialect names separated by '/', starting from the root language
+                 (the root's own name may be omitted)
+  [WORDS]        A file with one IPA word per line, otherwise read from stdin
+                 (stress marks and tone letters are carried through)
+  --format       Output format, defaults to tsv";
+
+#[derive(Clone, Copy)]
+enum Format {
+    Tsv,
+    Json
+}
+
+struct Args {
+    project: String,
+    path: String,
+    words: Option<String>,
+    format: Format
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut format = Format::Tsv;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+
+                process::exit(0);
+            },
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("tsv") => Format::Tsv,
+                    Some("json") => Format::Json,
+                    Some(other) => return Err(format!("Unknown format '{}'", other)),
+                    None => return Err(String::from("--format requires a value"))
+                };
+            },
+            _ => positional.push(arg)
+        }
+    }
+
+    let mut positional = positional.into_iter();
+
+    match (positional.next(), positional.next(), positional.next(), positional.next()) {
+        (Some(project), Some(path), words, None) => Ok(Args { project, path, words, format }),
+        _ => Err(String::from(USAGE))
+    }
+}
+
+/// Resolves each name in `path` to a node, beginning at the root
+/// The returned nodes always begin with the root itself
+fn resolve_path(state: &State, path: &str) -> Result<Vec<NodeIndex<u32>>, String> {
+    let name = |id: NodeIndex<u32>| state.dialects[state.language_tree[id]].name.clone();
+
+    let mut names = path
+        .split('/')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .peekable();
+
+    if names.peek().map_or(false, |first| *first == name(state.root).as_ref()) {
+        names.next();
+    }
+
+    let mut nodes = vec![state.root];
+    for target in names {
+        let current = *nodes.last().unwrap();
+
+        let child = state.language_tree
+            .neighbors_directed(current, petgraph::Outgoing)
+            .find(|child| name(*child).as_ref() == target)
+            .ok_or_else(|| format!("'{}' has no dialect named '{}'", name(current), target))?;
+
+        nodes.push(child);
+    }
+
+    Ok(nodes)
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+fn run(args: Args) -> Result<bool, String> {
+    let state = project::load(args.project.as_ref())
+        .map_err(|error| format!("Unable to open {}: {}", args.project, error))?;
+
+    let nodes = resolve_path(&state, &args.path)?;
+
+    let names = nodes
+        .iter()
+        .map(|id| state.dialects[state.language_tree[*id]].name.clone())
+        .collect::<Vec<_>>();
+
+    let input: Box<dyn BufRead> = match &args.words {
+        Some(path) => {
+            let file = fs::File::open(path)
+                .map_err(|error| format!("Unable to open {}: {}", path, error))?;
+
+            Box::new(io::BufReader::new(file))
+        },
+        None => Box::new(io::stdin().lock())
+    };
+
+    let lineage = state.inventory_lineage(state.root);
+
+    let mut complete = true;
+    let mut rows = Vec::new();
+
+    for (line, text) in input.lines().enumerate() {
+        let text = text.map_err(|error| format!("{}", error))?;
+        let text = text.trim();
+
+        if text.is_empty() { continue; }
+
+        let form = match parse_prosodic_word(text, &lineage) {
+            Some(form) => form,
+            None => {
+                eprintln!("Line {}: '{}' contains phonemes outside of the root language", line + 1, text);
+
+                complete = false;
+                continue;
+            }
+        };
+
+        let mut forms = vec![form];
+        for id in nodes.iter().skip(1) {
+            let (word, prosody) = forms.last().unwrap();
+            let form = state.derive(word, prosody, *id, None);
+
+            forms.push(form);
+        }
+
+        let forms = forms
+            .iter()
+            .map(|(word, prosody)| prosodic_word_to_string(&state.phonemes, word, prosody))
+            .collect::<Vec<_>>();
+
+        rows.push(forms);
+    }
+
+    let mut output = io::stdout().lock();
+
+    let written = match args.format {
+        Format::Tsv => {
+            let header = names
+                .iter()
+                .map(|name| name.as_ref())
+                .collect::<Vec<_>>();
+
+            writeln!(output, "{}", header.join("\t")).and_then(|_| {
+                rows.iter().try_for_each(|forms| writeln!(output, "{}", forms.join("\t")))
+            })
+        },
+        Format::Json => {
+            let rows = rows
+                .iter()
+                .map(|forms| {
+                    let reflexes = names
+                        .iter()
+                        .zip(forms.iter())
+                        .map(|(name, form)| {
+                            format!("{{\"dialect\": {}, \"form\": {}}}", escape_json(name), escape_json(form))
+                        })
+                        .collect::<Vec<_>>();
+
+                    format!("  [{}]", reflexes.join(", "))
+                })
+                .collect::<Vec<_>>();
+
+            writeln!(output, "[\n{}\n]", rows.join(",\n"))
+        }
+    };
+
+    written.map_err(|error| format!("{}", error))?;
+
+    Ok(complete)
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{}", error);
+
+            process::exit(2);
+        }
+    };
+
+    match run(args) {
+        Ok(true) => { /*  */ },
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("{}", error);
+
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use phonemenomicon::types::Language;
+
+    /// Adds a dialect named `name` under the node at `parent`
+    fn dialect(state: &mut State, parent: NodeIndex<u32>, name: &str) -> NodeIndex<u32> {
+        let language = Language { name: Rc::from(name), ..Default::default() };
+
+        let id = state.language_tree.add_node(state.dialects.insert(language));
+        state.language_tree.add_edge(parent, id, ());
+        id
+    }
+
+    #[test]
+    fn paths_are_resolved_from_the_root() {
+        let mut state = State::default();
+        let root = state.root;
+
+        state.dialects[state.inventory].name = Rc::from("Proto");
+
+        let west = dialect(&mut state, root, "West");
+        let east = dialect(&mut state, root, "East");
+        let coast = dialect(&mut state, west, "Coast");
+
+        assert_eq!(resolve_path(&state, "West/Coast"), Ok(vec![root, west, coast]));
+        assert_eq!(resolve_path(&state, "Proto / West / Coast"), Ok(vec![root, west, coast]));
+        assert_eq!(resolve_path(&state, "East/"), Ok(vec![root, east]));
+        assert_eq!(resolve_path(&state, ""), Ok(vec![root]));
+
+        assert_eq!(resolve_path(&state, "East/Coast"), Err(String::from("'East' has no dialect named 'Coast'")));
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(escape_json("tʃa˥"), "\"tʃa˥\"");
+        assert_eq!(escape_json("\"a\\b\""), "\"\\\"a\\\\b\\\"\"");
+        assert_eq!(escape_json("a\tb\nc"), "\"a\\tb\\nc\"");
+        assert_eq!(escape_json("\u{7}"), "\"\\u0007\"");
+    }
+}
@@ -1,12 +1,16 @@
-#![feature(const_discriminant)]
-#![feature(string_remove_matches)]
 #![warn(clippy::all, rust_2018_idioms)]
 
+#[cfg(feature = "gui")]
 mod app;
 mod state;
 
+pub mod project;
+
 pub mod types;
+
+#[cfg(feature = "gui")]
 pub mod pane;
 
+#[cfg(feature = "gui")]
 pub use app::App;
 pub use state::State;
\ No newline at end of file
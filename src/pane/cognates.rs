@@ -0,0 +1,107 @@
+use egui::RichText;
+use egui_extras::Column;
+
+use crate::app::FONT_ID;
+use crate::pane::Pane;
+use crate::types::{prosodic_word_to_string, is_valid_word};
+
+pub struct CognatePane {
+    romanized: bool
+}
+
+impl CognatePane {
+    pub fn new() -> Self {
+        Self { romanized: false }
+    }
+}
+
+impl Pane for CognatePane {
+    fn title(&self, _state: &crate::State) -> std::rc::Rc<str> {
+        std::rc::Rc::from("Cognates")
+    }
+
+    fn setup<'a, 'b: 'a>(&'a mut self, state: &crate::State, _ctx: &egui::Context) -> egui::Window<'b> {
+        egui::Window::new(self.title(state).as_ref())
+    }
+
+    fn show(&mut self, _windowed: bool, state: &mut crate::State, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let root = &state.dialects[state.language_tree[state.root]];
+
+            ui.label(format!("Showing the reflexes of every word in {}", root.name));
+
+            ui.separator();
+
+            ui.checkbox(&mut self.romanized, "Romanize");
+        });
+
+        let dialects = state.dialect_order();
+
+        let orthographies = dialects
+            .iter()
+            .map(|id| (state.lineage(*id), state.orthography(*id)))
+            .collect::<Vec<_>>();
+
+        let lexicon = &state.dialects[state.language_tree[state.root]].lexicon;
+
+        let mut entries = lexicon.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(_, entry)| entry.gloss.to_lowercase());
+
+        let row_height = FONT_ID.size;
+        let row_height = row_height + ui.style().spacing.item_spacing.y * 2.;
+
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            egui_extras::TableBuilder::new(ui)
+                .striped(true)
+                .column(Column::auto().at_least(FONT_ID.size * 4.))
+                .columns(Column::auto().at_least(FONT_ID.size * 4.), dialects.len())
+                .vscroll(true)
+                .header(row_height, |mut header| {
+                    header.col(|ui| { ui.strong("Gloss"); });
+
+                    for id in dialects.iter() {
+                        header.col(|ui| { 
+                            ui.strong(state.dialects[state.language_tree[*id]].name.as_ref()); 
+                        });
+                    }
+                })
+                .body(|mut body| {
+                    for (key, entry) in entries {
+                        body.row(row_height, |mut row| {
+                            row.col(|ui| { ui.label(entry.gloss.as_str()); });
+
+                            // Words holding removed phonemes aren't derived
+                            if !is_valid_word(&state.phonemes, &entry.form) {
+                                row.col(|ui| {
+                                    ui.colored_label(ui.visuals().error_fg_color, "⚠ Invalid")
+                                        .on_hover_text("Written with phonemes that were removed from the inventory");
+                                });
+
+                                for _ in 1..dialects.len() {
+                                    row.col(|_| { /*  */ });
+                                }
+
+                                return;
+                            }
+
+                            let reflexes = state.propagate(&entry.form, &entry.prosody, Some(key));
+
+                            for ((_, reflex, prosody), (lineage, orthography)) in reflexes.into_iter().zip(orthographies.iter()) {
+                                row.col(|ui| {
+                                    let content = if self.romanized {
+                                        orthography.romanize(lineage, &reflex)
+                                    } else {
+                                        format!("/{}/", prosodic_word_to_string(&state.phonemes, &reflex, &prosody))
+                                    };
+
+                                    ui.label(RichText::new(content).font(FONT_ID.to_owned()));
+                                });
+                            }
+                        });
+                    }
+                });
+        });
+    }
+
+    fn on_dialect_change(&mut self, _state: &mut crate::State) { /* */ }
+}
@@ -3,7 +3,7 @@ use std::collections::HashSet;
 use enum_iterator::all;
 use slotmap::SlotMap;
 
-use crate::types::{Phoneme, PhonemeQuality, Alphabet, Phone};
+use crate::types::{Phoneme, PhonemeQuality, Alphabet, Phone, Length, Diacritic};
 use crate::types::category::{Outer, Inner, Pair, Category};
 use crate::types::category::{Articulation, Region, Voicing, Constriction, Place, Rounding};
 
@@ -38,6 +38,54 @@ fn excl<T: Category>(excluding: &[T]) -> Vec<T> {
     collection.into_iter().collect::<Vec<_>>()
 }
 
+fn marked<A: Category, B: Category, C: Category>(
+    restriction: impl Into<PhonemeQuality<A, B, C>>,
+    diacritic: Diacritic) -> Modifier<A, B, C> {
+
+    trans(restriction, diacritic.mark().to_string().as_str(), format!("{}", diacritic).as_str())
+}
+
+/// Modifiers are listed by their marks, each of which belongs to a single diacritic
+fn find_marked(symbol: &str) -> Option<Diacritic> {
+    symbol.chars().next().and_then(Diacritic::from_mark)
+}
+
+fn add_marked(phoneme: &mut Phoneme, symbol: &str) {
+    if let Some(diacritic) = find_marked(symbol) {
+        phoneme.diacritics.insert(diacritic);
+    }
+}
+
+fn marked_behavior() -> DiacriticsBehavior {
+    DiacriticsBehavior::Multiple { 
+        contains: |phoneme: &Phoneme, symbol: &str| 
+            find_marked(symbol).map_or(false, |diacritic| phoneme.diacritics.contains(&diacritic)), 
+        remove: |phoneme: &mut Phoneme, symbol: &str| {
+            if let Some(diacritic) = find_marked(symbol) {
+                phoneme.diacritics.remove(&diacritic);
+            }
+        }
+    }
+}
+
+/// Length is a property of the phoneme rather than part of its symbol
+fn length<A: Outer<B, C>, B: Inner<C>, C: Pair>() -> Diacritics<A, B, C> {
+    Diacritics {
+        category: "Length",
+        contents: vec![
+            trans((&[][..], &[][..], &[][..]), Length::Long.mark(), "Long"),
+            trans((&[][..], &[][..], &[][..]), Length::HalfLong.mark(), "Half-Long")
+        ],
+        change_state: |phoneme: &mut Phoneme, symbol: &str|
+            phoneme.length = Length::from_mark(symbol),
+        behavior: DiacriticsBehavior::Single {
+            contains: |phoneme: &Phoneme| -> bool { phoneme.length != Length::Short },
+            remove: |phoneme: &mut Phoneme| phoneme.length = Length::Short
+        },
+        prepend_blank: true
+    }
+}
+
 fn trans<A: Category, B: Category, C: Category>(
     restriction: impl Into<PhonemeQuality<A, B, C>>, 
     symbol: &str, 
@@ -96,7 +144,7 @@ pub fn modifiers_consonants(
             behavior: DiacriticsBehavior::Single { 
                 contains: |phoneme: &Phoneme| -> bool {
                     if let Phone::Consonant { affricated, .. } = &phoneme.phone {
-                        return affricated.is_some();
+                        affricated.is_some()
                     } else {
                         unreachable!();
                     }
@@ -121,10 +169,10 @@ pub fn modifiers_consonants(
 
             vec![
                 trans((&[][..], &[][..], &[][..]), "ʰ", "Aspirated"),
-                trans((&[][..], excl(&[Bilabial, Labiodental]).as_slice(), &[][..]), "ʷ", "Labialized"),
-                trans((&[][..], excl(&[Palatal]).as_slice(), &[][..]), "ʲ", "Palatalized"),
-                trans((&[][..], excl(&[Velar]).as_slice(), &[][..]), "ˠ", "Velarized"),
-                trans((&[][..], excl(&[Pharyngeal]).as_slice(), &[][..]), "ˤ", "Pharyngealized"),
+                trans((&[][..], excl(&[Bilabial, Labiodental, LabialPalatal, LabialVelar]).as_slice(), &[][..]), "ʷ", "Labialized"),
+                trans((&[][..], excl(&[Palatal, AlveoloPalatal, LabialPalatal]).as_slice(), &[][..]), "ʲ", "Palatalized"),
+                trans((&[][..], excl(&[Velar, LabialVelar, PostVelar]).as_slice(), &[][..]), "ˠ", "Velarized"),
+                trans((&[][..], excl(&[Pharyngeal, Epiglottal]).as_slice(), &[][..]), "ˤ", "Pharyngealized"),
                 trans((&[][..], excl(&[Glottal]).as_slice(), &[][..]), "ˀ", "Glottalized"),
                 trans((&[][..], &[][..], &[][..]), "ⁿ", "Nasal Release"),
                 trans((&[][..], &[][..], &[][..]), "ˡ", "Lateral Release")
@@ -135,7 +183,7 @@ pub fn modifiers_consonants(
         behavior: DiacriticsBehavior::Single { 
             contains: |phoneme: &Phoneme| -> bool {
                 if let Phone::Consonant { regionalized, .. } = &phoneme.phone {
-                    return regionalized.is_some();
+                    regionalized.is_some()
                 } else {
                     unreachable!();
                 }
@@ -151,6 +199,8 @@ pub fn modifiers_consonants(
         prepend_blank: true,
     });
 
+    diacritics.push(length());
+
     // CONSONANT DIACRITICS
     diacritics.push(Diacritics { 
         category: "Quality", 
@@ -160,34 +210,27 @@ pub fn modifiers_consonants(
             use Articulation::*;
 
             vec![
-                trans((&[][..], &[][..], &[Voiced][..]), "\u{030A}", "Voiceless"),
-                trans((&[][..], &[][..], &[Voiceless][..]), "\u{032C}", "Voiced"),
-                trans((&[][..], &[][..], &[][..]), "\u{031F}", "Advanced"),
-                trans((&[][..], &[][..], &[][..]), "\u{0320}", "Retracted"),
-                trans((&[][..], &[][..], &[][..]), "\u{0324}", "Breathy"),
-                trans((&[][..], &[][..], &[][..]), "\u{0330}", "Creaky"),
-                trans((&[][..], &[][..], &[][..]), "\u{033C}", "Linguolabial"),
-                trans((&[][..], excl(&[Velar, Pharyngeal][..]).as_slice(), &[][..]), 
-                    "̴", "Velarized or Pharyngealized"),
-                trans((&[][..], &[][..], &[][..]), "\u{031D}", "Raised"),
-                trans((&[][..], &[][..], &[][..]), "\u{031E}", "Lowered"),
-                trans((&[][..], excl(&[Dental][..]).as_slice(), &[][..]), 
-                    "\u{032A}", "Dental"),
-                trans((&[][..], &[][..], &[][..]), "\u{033A}", "Apical"),
-                trans((&[][..], &[][..], &[][..]), "\u{033B}", "Laminal"),
-                trans((Plosive, &[][..], &[][..]), "\u{033A}", "Applosive"),
-                trans((excl(&[Plosive, Fricative, LatFricative][..]).as_slice(), &[][..], &[][..]), 
-                    "\u{033B}", "Syllabic")
+                marked((&[][..], &[][..], &[Voiced][..]), Diacritic::Voiceless),
+                marked((&[][..], &[][..], &[Voiceless][..]), Diacritic::Voiced),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Advanced),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Retracted),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Breathy),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Creaky),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Linguolabial),
+                marked((&[][..], excl(&[Velar, Pharyngeal][..]).as_slice(), &[][..]), 
+                    Diacritic::VelarizedOrPharyngealized),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Raised),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Lowered),
+                marked((&[][..], excl(&[Dental][..]).as_slice(), &[][..]), Diacritic::Dental),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Apical),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Laminal),
+                marked((Plosive, &[][..], &[][..]), Diacritic::NoAudibleRelease),
+                marked((excl(&[Plosive, Fricative, LatFricative, Click, LatClick, Implosive, Ejective, EjFricative][..]).as_slice(), &[][..], &[][..]), 
+                    Diacritic::Syllabic)
             ]
         }, 
-        change_state: |phoneme: &mut Phoneme, symbol: &str| 
-            phoneme.add_diacritic(symbol), 
-        behavior: DiacriticsBehavior::Multiple { 
-            contains: |phoneme: &Phoneme, symbol: &str| 
-                format!("{}", phoneme).contains(symbol), 
-            remove: |phoneme: &mut Phoneme, symbol: &str|
-                phoneme.symbol.remove_matches(symbol)
-        }, 
+        change_state: add_marked, 
+        behavior: marked_behavior(), 
         prepend_blank: true 
     });
 
@@ -200,36 +243,28 @@ pub fn modifiers_vowels(
     ipa: &Alphabet<Constriction, Place, Rounding>,
     quality: PhonemeQuality<Constriction, Place, Rounding>
 ) -> impl Iterator<Item = Diacritics<Constriction, Place, Rounding>> {
-    let mut diacritics = Vec::new();
+    let mut diacritics = vec![length()];
 
     diacritics.push(Diacritics { 
         category: "Quality", 
         contents: {
-            use Constriction::*;
             use Place::*;
             use Rounding::*;
             
             vec![
-                trans((&[][..], &[][..], &[Unrounded][..]), "̹", "Rounded"),
-                trans((&[][..], &[][..], &[Rounded][..]), "̹", "Rounded"),
-                trans((&[][..], excl(&[Central]).as_slice(), &[][..]), "\u{0308}", "Centralized"),
-                trans((excl(&[Mid]).as_slice(), excl(&[Central]).as_slice(), &[][..]), 
-                    "\u{033D}", "Mid-Centralized"), // TODO: This is problematic, should show for all but ə
-                trans((&[][..], &[][..], &[][..]), "\u{032F}", "Non-Syllabic"),
-                trans((&[][..], &[][..], &[][..]), "\u{0303}", "Nasal"),
-                trans((&[][..], &[][..], &[][..]), "\u{0318}", "Advanced Tongue Root"),
-                trans((&[][..], &[][..], &[][..]), "\u{0319}", "Retracted Tongue Root"),
-                trans((&[][..], &[][..], &[][..]), "˞", "R-Colored")
+                marked((&[][..], &[][..], &[Unrounded][..]), Diacritic::MoreRounded),
+                marked((&[][..], &[][..], &[Rounded][..]), Diacritic::LessRounded),
+                marked((&[][..], excl(&[Central]).as_slice(), &[][..]), Diacritic::Centralized),
+                marked((&[][..], excl(&[Central]).as_slice(), &[][..]), Diacritic::MidCentralized),
+                marked((&[][..], &[][..], &[][..]), Diacritic::NonSyllabic),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Nasalized),
+                marked((&[][..], &[][..], &[][..]), Diacritic::AdvancedTongueRoot),
+                marked((&[][..], &[][..], &[][..]), Diacritic::RetractedTongueRoot),
+                marked((&[][..], &[][..], &[][..]), Diacritic::Rhotic)
             ]
         }, 
-        change_state: |phoneme: &mut Phoneme, symbol: &str|
-            phoneme.add_diacritic(symbol), 
-        behavior: DiacriticsBehavior::Multiple { 
-            contains: |phoneme: &Phoneme, symbol: &str| 
-                format!("{}", phoneme).contains(symbol), 
-            remove: |phoneme: &mut Phoneme, symbol: &str|
-                phoneme.symbol.remove_matches(symbol)
-        }, 
+        change_state: add_marked, 
+        behavior: marked_behavior(), 
         prepend_blank: true 
     });
 
@@ -5,7 +5,7 @@ use std::mem;
 use egui_extras::Column;
 use slotmap::SlotMap;
 
-use crate::types::{Alphabet, Phoneme, CONSONANT, VOWEL, Language, PhonemeQuality};
+use crate::types::{Alphabet, Phoneme, Language, PhonemeQuality, Feature, Features};
 use crate::types::category::{Outer, Inner, Pair};
 use crate::types::category::{Articulation, Region, Voicing, Constriction, Place, Rounding};
 
@@ -21,7 +21,7 @@ fn show_row_content<A, B, C>(
         let content = if diacritics.prepend_blank {
             format!("\u{25CC}{}", modifier)
         } else {
-            format!("{}", modifier)
+            modifier.to_string()
         };
     
         let content =  egui::RichText::new(content)
@@ -39,12 +39,12 @@ fn show_row_content<A, B, C>(
             diacritics::DiacriticsBehavior::Multiple { 
                 contains, remove } => {
                 
-                let mut state = (contains)(&phoneme, &modifier);
+                let mut state = (contains)(phoneme, modifier);
                 if ui.toggle_value(&mut state, content).clicked() {
                     if !state {
-                        (remove)(phoneme, &modifier);
+                        (remove)(phoneme, modifier);
                     } else {
-                        (diacritics.change_state)(phoneme, &modifier);
+                        (diacritics.change_state)(phoneme, modifier);
                     }
                     
                     ui.close_menu();
@@ -73,7 +73,7 @@ fn show_rows<A, B, C>(
                 if quality.meets_restrictions(restriction.clone()) {
                     
                     body.row(row_height, |row| {
-                        show_row_content(row, &diacritics, phoneme, &modifier, &desc);
+                        show_row_content(row, &diacritics, phoneme, modifier, desc);
                     });
                 }
             }
@@ -89,7 +89,7 @@ fn diacritics_display<A, B, C>(
 
     match diacritics.behavior {
         diacritics::DiacriticsBehavior::Single { contains, remove } => {
-            if (contains)(&phoneme) {
+            if (contains)(phoneme) {
                 let content = format!("Remove {}", diacritics.category);
                 if ui.button(content).clicked() {
                     (remove)(phoneme);
@@ -111,8 +111,35 @@ fn diacritics_display<A, B, C>(
     }
 }
 
+/// Lists every feature of a phoneme, leaving those that don't apply to it blank
+fn features_display(ui: &mut egui::Ui, features: Features) {
+    ui.menu_button("Features", |ui| {
+        egui::Grid::new("context-features").striped(true).show(ui, |ui| {
+            for feature in enum_iterator::all::<Feature>() {
+                let value = match features.get(feature) {
+                    Some(true) => "+",
+                    Some(false) => "\u{2212}",
+                    None => ""
+                };
+
+                ui.label(egui::RichText::new(value).font(FONT_ID.to_owned()));
+                ui.label(format!("{}", feature));
+
+                ui.end_row();
+            }
+        });
+    });
+}
+
 pub enum Context<'a, A: Outer<B, C>, B: Inner<C>, C: Pair> {
-    Bound { inventory: &'a mut Alphabet<A, B, C>, id: slotmap::DefaultKey },
+    /// The phoneme can't be removed unless `removable`,
+    /// and is only put in `removal` so that the caller removes it through `State::remove_phoneme`
+    Bound { 
+        inventory: &'a mut Alphabet<A, B, C>, 
+        id: slotmap::DefaultKey, 
+        removable: bool, 
+        removal: &'a mut Option<slotmap::DefaultKey> 
+    },
     Free { quality: PhonemeQuality<A, B, C>, phoneme: &'a mut Phoneme }
 }
 
@@ -124,12 +151,12 @@ pub fn cell_context<A: Outer<B, C>, B: Inner<C>, C: Pair>(
     context: Context<'_, A, B, C>) {
     
     match context {
-        Context::Bound { inventory, id } => {
+        Context::Bound { inventory, id, removable, removal } => {
             // TODO: There must be a better way
             let quality = inventory.get_quality(id).unwrap();
 
             type Src<A, B, C> = PhonemeQuality<A, B, C>;
-            if mem::discriminant(&phonemes[id].phone) == CONSONANT {
+            if phonemes[id].phone.is_consonant() {
                 let quality = unsafe {
                     type Dst = PhonemeQuality<Articulation, Region, Voicing>;
                     mem::transmute::<&Src<A, B, C>, &Dst>(&quality)
@@ -140,7 +167,9 @@ pub fn cell_context<A: Outer<B, C>, B: Inner<C>, C: Pair>(
                     
                     diacritics_display(ui, diacritics, quality.clone(), &mut phonemes[id]);
                 }
-            } else if mem::discriminant(&phonemes[id].phone) == VOWEL {
+
+                features_display(ui, phonemes[id].consonant_features(quality.clone()));
+            } else if phonemes[id].phone.is_vowel() {
                 let quality = unsafe {
                     type Dst = PhonemeQuality<Constriction, Place, Rounding>;
                     mem::transmute::<&Src<A, B, C>, &Dst>(&quality)
@@ -151,22 +180,26 @@ pub fn cell_context<A: Outer<B, C>, B: Inner<C>, C: Pair>(
                     
                     diacritics_display(ui, diacritics, quality.clone(), &mut phonemes[id]);
                 }
+
+                features_display(ui, phonemes[id].vowel_features(quality.clone()));
             } else {
                 unreachable!();
             }
 
             let content = egui::RichText::new("Remove Phoneme").italics();
 
-            if ui.button(content).clicked() {
-                phonemes.remove(id);
-                inventory.remove_phoneme(id);
+            let response = ui.add_enabled(removable, egui::Button::new(content))
+                .on_disabled_hover_text("Words of the lexicon are written with this phoneme");
+
+            if response.clicked() {
+                let _ = removal.insert(id);
         
                 ui.close_menu();
             }
         },
         Context::Free { quality, phoneme } => {
             type Src<A, B, C> = PhonemeQuality<A, B, C>;
-            if mem::discriminant(&phoneme.phone) == CONSONANT {
+            if phoneme.phone.is_consonant() {
                 let quality = unsafe {
                     type Dst = PhonemeQuality<Articulation, Region, Voicing>;
                     mem::transmute::<&Src<A, B, C>, &Dst>(&quality)
@@ -177,7 +210,9 @@ pub fn cell_context<A: Outer<B, C>, B: Inner<C>, C: Pair>(
                     
                     diacritics_display(ui, diacritics, quality.clone(), phoneme);
                 }
-            } else if mem::discriminant(&phoneme.phone) == VOWEL {
+
+                features_display(ui, phoneme.consonant_features(quality.clone()));
+            } else if phoneme.phone.is_vowel() {
                 let quality = unsafe {
                     type Dst = PhonemeQuality<Constriction, Place, Rounding>;
                     mem::transmute::<&Src<A, B, C>, &Dst>(&quality)
@@ -188,6 +223,8 @@ pub fn cell_context<A: Outer<B, C>, B: Inner<C>, C: Pair>(
                     
                     diacritics_display(ui, diacritics, quality.clone(), phoneme);
                 }
+
+                features_display(ui, phoneme.vowel_features(quality.clone()));
             } else {
                 unreachable!();
             }
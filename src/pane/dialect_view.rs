@@ -5,26 +5,168 @@ use petgraph::stable_graph::{NodeIndex, StableGraph};
 
 use crate::{pane::Pane, types::Language};
 
+/// Where a dragged dialect lands relative to the dialect it's dropped on
+#[derive(Clone, Copy, PartialEq)]
+enum Placement {
+    Before,
+    Child,
+    After
+}
+
 pub struct DialectPane {
     current: String,
-    renaming: Option<NodeIndex<u32>>
+    renaming: Option<NodeIndex<u32>>,
+    /// A dialect with descendants, which are only deleted once the user confirms it
+    deleting: Option<NodeIndex<u32>>,
+    dragging: Option<NodeIndex<u32>>,
+    drop_target: Option<(NodeIndex<u32>, Placement)>
 }
 
 impl DialectPane {
     pub fn new() -> Self {
         Self {
             current: String::from(""),
-            renaming: None
+            renaming: None,
+            deleting: None,
+            dragging: None,
+            drop_target: None
+        }
+    }
+
+    /// Moves the dragged dialect to where it was dropped, once the pointer is released
+    fn finish_drag(&mut self, state: &mut crate::State, ctx: &egui::Context) {
+        let dragging = match self.dragging {
+            Some(dragging) if state.language_tree.contains_node(dragging) => dragging,
+            _ => {
+                let _ = self.dragging.take();
+
+                return;
+            }
+        };
+
+        ctx.set_cursor_icon(egui::CursorIcon::Grabbing);
+
+        egui::show_tooltip_at_pointer(ctx, egui::Id::new("dialect-view-dragging"), |ui| {
+            ui.label(state.dialects[state.language_tree[dragging]].name.as_ref());
+        });
+
+        if !ctx.input(|input| input.pointer.any_released()) { return; }
+
+        match self.drop_target.take() {
+            Some((target, Placement::Child)) => state.move_dialect(dragging, target, usize::MAX),
+            Some((target, placement)) => {
+                let parent = state.parent(target).unwrap();
+
+                let position = state.children(parent)
+                    .into_iter()
+                    .filter(|child| *child != dragging)
+                    .position(|child| child == target)
+                    .unwrap();
+
+                let position = if placement == Placement::After { position + 1 } else { position };
+
+                state.move_dialect(dragging, parent, position);
+            },
+            None => { /*  */ }
+        }
+
+        let _ = self.dragging.take();
+    }
+
+    /// Lists everything that deleting the subtree would remove, and deletes it if the user confirms
+    fn show_deletion(&mut self, state: &mut crate::State, ctx: &egui::Context) {
+        let id = match self.deleting {
+            Some(id) if state.language_tree.contains_node(id) => id,
+            _ => {
+                let _ = self.deleting.take();
+
+                return;
+            }
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        egui::Window::new("Delete Dialects")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("The following dialects will be deleted, along with everything they own");
+
+                egui::Grid::new("dialect-view-deletion").striped(true).show(ui, |ui| {
+                    for node in state.subtree(id) {
+                        let dialect = &state.dialects[state.language_tree[node]];
+
+                        ui.strong(dialect.name.as_ref());
+                        ui.label(format!("{} phonemes", dialect.phonemes().count()));
+                        ui.label(format!("{} sound changes", dialect.sound_changes.len()));
+                        ui.label(format!("{} words", dialect.lexicon.len()));
+                        ui.end_row();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Delete").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+
+        if confirmed {
+            state.remove_dialect(id);
+        }
+
+        if confirmed || cancelled {
+            let _ = self.deleting.take();
         }
     }
 }
 
+/// Marks where the dragged dialect would land if it were dropped on the dialect at `id`
+/// Dialects can't be dropped beside the root, or anywhere within themselves
+fn show_drop_target(
+    ui: &mut egui::Ui, 
+    state: &crate::State, 
+    id: NodeIndex<u32>, 
+    pane: &mut DialectPane, 
+    rect: egui::Rect) {
+
+    match pane.dragging {
+        Some(dragging) if !state.subtree(dragging).contains(&id) => { /*  */ },
+        _ => return
+    }
+
+    let pointer = match ui.input(|input| input.pointer.hover_pos()) {
+        Some(pointer) if rect.contains(pointer) => pointer,
+        _ => return
+    };
+
+    let fraction = (pointer.y - rect.top()) / rect.height();
+
+    let placement = match state.parent(id) {
+        Some(..) if fraction < 1. / 3. => Placement::Before,
+        Some(..) if fraction > 2. / 3. => Placement::After,
+        _ => Placement::Child
+    };
+
+    let stroke = ui.visuals().selection.stroke;
+    let gap = ui.style().spacing.item_spacing.y / 2.;
+
+    match placement {
+        Placement::Before => ui.painter().hline(rect.x_range(), rect.top() - gap, stroke),
+        Placement::Child => ui.painter().rect_stroke(rect, 0., stroke),
+        Placement::After => ui.painter().hline(rect.x_range(), rect.bottom() + gap, stroke)
+    }
+
+    let _ = pane.drop_target.insert((id, placement));
+}
+
 fn show_dialect_internal(
     ui: &mut egui::Ui, 
     state: &mut crate::State,
     id: NodeIndex<u32>,
-    renaming: &mut Option<NodeIndex<u32>>,
-    current: &mut String) -> egui::Pos2 {
+    pane: &mut DialectPane) -> egui::Pos2 {
+
+    let DialectPane { renaming, current, .. } = pane;
 
     let dialect_name = state.dialects[state.language_tree[id]].name.clone();
 
@@ -55,7 +197,8 @@ fn show_dialect_internal(
                 dialect_button = dialect_button.fill(dialect_button_color);
             }
     
-            let response = ui.add(dialect_button);
+            // The root stays where it is, every other dialect can be dragged to a new place in the tree
+            let response = ui.add(dialect_button).interact(egui::Sense::drag());
     
             let dialect_button_id = response.id;
         
@@ -64,6 +207,14 @@ fn show_dialect_internal(
                 state.inventory_index = id;
             }
 
+            if response.drag_started() && state.parent(id).is_some() {
+                let _ = pane.dragging.insert(id);
+            }
+
+            show_drop_target(ui, state, id, pane, response.rect);
+
+            let DialectPane { renaming, deleting, current, .. } = pane;
+
             let dialect_button_center = response.rect.center();
         
             response.context_menu(|ui| {
@@ -95,24 +246,26 @@ fn show_dialect_internal(
                     current.clear();
                 }
 
-                if state.language_tree
-                    .neighbors_directed(id, petgraph::Outgoing)
-                    .count() == 0 {
-
-                    if let Some(parent) =  state.language_tree
-                        .neighbors_directed(id, petgraph::Incoming)
-                        .next() {
-                            
-                        if ui.button("Delete").clicked() {
-                            let edge = state.language_tree
-                                .find_edge(parent, id)
-                                .unwrap();
-
-                            // TODO: Must also remove language from `state.dialects`
-                            state.language_tree.remove_edge(edge);
-                            state.language_tree.remove_node(id);
-                        }
+                if state.parent(id).is_some() && ui.button("Duplicate Dialect").clicked() {
+                    state.duplicate_dialect(id);
+
+                    ui.close_menu();
+                }
+
+                if state.parent(id).is_some() && ui.button("Delete").clicked() {
+                    let leaf = state.language_tree
+                        .neighbors_directed(id, petgraph::Outgoing)
+                        .next()
+                        .is_none();
+
+                    // Deleting a whole subtree is confirmed first
+                    if leaf {
+                        state.remove_dialect(id);
+                    } else {
+                        let _ = deleting.insert(id);
                     }
+
+                    ui.close_menu();
                 }
             });
 
@@ -147,8 +300,7 @@ fn show_dialect(
     ui: &mut egui::Ui, 
     state: &mut crate::State,
     id: NodeIndex<u32>,
-    renaming: &mut Option<NodeIndex<u32>>,
-    current: &mut String,
+    pane: &mut DialectPane,
     cell_width: f32) -> egui::Pos2 {
 
     let row_height = ui.style().text_styles[&egui::TextStyle::Button].size;
@@ -182,18 +334,18 @@ fn show_dialect(
                         .sizes(Size::exact(cell_width), 2)
                         .horizontal(|mut strip| {
                             strip.cell(|ui| { 
-                                temp = show_dialect_internal(ui, state, id, renaming, current);
+                                temp = show_dialect_internal(ui, state, id, pane);
                             });
 
                             strip.cell(|ui| {
-                                let to = show_dialect(ui, state, *child, renaming, current, cell_width); 
+                                let to = show_dialect(ui, state, *child, pane, cell_width); 
                                 show_connection(ui, temp, to);
                             });
                         });
 
                     temp
                 },
-                None => show_dialect_internal(ui, state, id, renaming, current)
+                None => show_dialect_internal(ui, state, id, pane)
             };
         });
 
@@ -205,7 +357,7 @@ fn show_dialect(
                         strip.empty();
 
                         strip.cell(|ui| { 
-                            let to = show_dialect(ui, state, child, renaming, current, cell_width); 
+                            let to = show_dialect(ui, state, child, pane, cell_width); 
                             show_connection(ui, pos, to);
                         });
                     });
@@ -246,9 +398,13 @@ impl Pane for DialectPane {
             let depth = depth(&state.language_tree, state.root);
             let cell_width = ui.available_width() / depth as f32;
 
-            show_dialect(ui, state, state.root,
-                &mut self.renaming, &mut self.current, cell_width);
+            let _ = self.drop_target.take();
+
+            show_dialect(ui, state, state.root, self, cell_width);
         });
+
+        self.finish_drag(state, ui.ctx());
+        self.show_deletion(state, ui.ctx());
     }
 
     fn on_dialect_change(&mut self, _state: &mut crate::State) { /* */ }
@@ -0,0 +1,172 @@
+use egui::RichText;
+use egui_extras::Column;
+
+use crate::app::FONT_ID;
+use crate::pane::Pane;
+use crate::types::{Entry, Word};
+use crate::types::word_to_string;
+
+pub struct GeneratorPane {
+    count: usize,
+    generated: Vec<Word>
+}
+
+impl GeneratorPane {
+    pub fn new() -> Self {
+        Self {
+            count: 10,
+            generated: Vec::new()
+        }
+    }
+
+    fn show_settings(&mut self, state: &mut crate::State, ui: &mut egui::Ui) {
+        let generator = &mut state.dialects[state.inventory].generator;
+
+        ui.horizontal(|ui| {
+            ui.label("Seed");
+            ui.add(egui::DragValue::new(&mut generator.seed));
+
+            if ui.button("New Seed").clicked() {
+                // The time since startup is as good a source of entropy as any
+                generator.seed = ui.input(|input| input.time.to_bits());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let (min, max) = &mut generator.syllables;
+
+            ui.label("Syllables");
+            ui.add(egui::DragValue::new(min).clamp_range(1..=16));
+            ui.label("to");
+            ui.add(egui::DragValue::new(max).clamp_range(*min..=16));
+
+            ui.separator();
+
+            ui.label("Words");
+            ui.add(egui::DragValue::new(&mut self.count).clamp_range(1..=256));
+        });
+
+        ui.collapsing("Phoneme Weights", |ui| {
+            let phonemes = state.inventory_lineage(state.inventory_index).sounds();
+
+            let language = &mut state.dialects[state.inventory];
+
+            ui.horizontal_wrapped(|ui| {
+                for (symbol, id) in phonemes {
+                    let mut weight = language.generator.weight(id);
+
+                    ui.label(RichText::new(symbol).font(FONT_ID.to_owned()));
+
+                    let weight_editor = egui::DragValue::new(&mut weight)
+                        .speed(0.05)
+                        .clamp_range(0.0..=10.0);
+
+                    if ui.add(weight_editor).changed() {
+                        language.generator.weights.insert(id, weight);
+                    }
+
+                    ui.separator();
+                }
+            });
+        });
+    }
+}
+
+impl Pane for GeneratorPane {
+    fn title(&self, _state: &crate::State) -> std::rc::Rc<str> {
+        std::rc::Rc::from("Word Generator")
+    }
+
+    fn setup<'a, 'b: 'a>(&'a mut self, state: &crate::State, _ctx: &egui::Context) -> egui::Window<'b> {
+        egui::Window::new(self.title(state).as_ref())
+    }
+
+    fn show(&mut self, _windowed: bool, state: &mut crate::State, ui: &mut egui::Ui) {
+        self.show_settings(state, ui);
+
+        ui.horizontal(|ui| {
+            if ui.button("Generate").clicked() {
+                let lineage = state.inventory_lineage(state.inventory_index);
+
+                let language = &state.dialects[state.inventory];
+
+                self.generated = language.generate_words(&lineage, self.count);
+            }
+
+            if !self.generated.is_empty() && ui.button("Add All to Lexicon").clicked() {
+                let seed = state.dialects[state.inventory].generator.seed;
+
+                for form in self.generated.drain(0..) {
+                    let entry = Entry { 
+                        form, 
+                        notes: format!("Generated from seed {}", seed), 
+                        ..Default::default() 
+                    };
+
+                    state.dialects[state.inventory].lexicon.insert(entry);
+                }
+            }
+        });
+
+        if state.dialects[state.inventory].phonotactics.templates.is_empty() {
+            ui.label("Add a syllable template in the Phonotactics pane to generate words");
+        }
+
+        ui.separator();
+
+        let row_height = FONT_ID.size;
+        let row_height = row_height + ui.style().spacing.item_spacing.y * 2.;
+        let row_height = row_height + ui.style().spacing.button_padding.y * 2.;
+
+        let mut addition = None;
+
+        let lineage = state.lineage(state.inventory_index);
+
+        let orthography = state.orthography(state.inventory_index);
+
+        egui_extras::TableBuilder::new(ui)
+            .striped(true)
+            .columns(Column::remainder(), 2)
+            .column(Column::auto())
+            .vscroll(true)
+            .body(|mut body| {
+                for (idx, word) in self.generated.iter().enumerate() {
+                    body.row(row_height, |mut row| {
+                        row.col(|ui| {
+                            let content = format!("/{}/", word_to_string(&state.phonemes, word));
+
+                            ui.label(RichText::new(content).font(FONT_ID.to_owned()));
+                        });
+
+                        row.col(|ui| {
+                            let content = orthography.romanize(&lineage, word);
+
+                            ui.label(RichText::new(content).font(FONT_ID.to_owned()).italics());
+                        });
+
+                        row.col(|ui| {
+                            if ui.button("Add").clicked() {
+                                let _ = addition.insert(idx);
+                            }
+                        });
+                    });
+                }
+            });
+
+        if let Some(idx) = addition {
+            let seed = state.dialects[state.inventory].generator.seed;
+
+            let entry = Entry {
+                form: self.generated.remove(idx),
+                notes: format!("Generated from seed {}", seed),
+                ..Default::default()
+            };
+
+            state.dialects[state.inventory].lexicon.insert(entry);
+        }
+    }
+
+    fn on_dialect_change(&mut self, _state: &mut crate::State) {
+        self.generated.clear();
+    }
+}
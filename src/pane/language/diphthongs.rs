@@ -0,0 +1,144 @@
+use egui_extras::Column;
+use enum_map::Enum;
+use slotmap::DefaultKey;
+
+use crate::app::FONT_ID;
+use crate::pane::util;
+use crate::types::{Phone, PhonemeQuality};
+use crate::types::category::{Constriction, Place, Rounding};
+
+fn describe(quality: (Constriction, Place, Rounding)) -> String {
+    let (constriction, place, rounding) = quality;
+
+    format!("{} {} {}", constriction, place, rounding)
+}
+
+/// The vowels of a diphthong that has yet to be added
+#[derive(Default)]
+pub struct DiphthongDraft {
+    start: Option<DefaultKey>,
+    end: Option<DefaultKey>
+}
+
+fn vowel_selector(
+    ui: &mut egui::Ui,
+    id: &str,
+    vowels: &[(DefaultKey, String)],
+    selected: &mut Option<DefaultKey>) {
+
+    let text = vowels
+        .iter()
+        .find(|(id, _)| Some(*id) == *selected)
+        .map_or("...", |(_, symbol)| symbol.as_str());
+
+    egui::ComboBox::from_id_source(id)
+        .selected_text(egui::RichText::new(text).font(FONT_ID.to_owned()))
+        .show_ui(ui, |ui| {
+            for (id, symbol) in vowels.iter() {
+                let content = egui::RichText::new(symbol).font(FONT_ID.to_owned());
+
+                ui.selectable_value(selected, Some(*id), content);
+            }
+        });
+}
+
+pub fn show_diphthongs(ui: &mut egui::Ui, state: &mut crate::State, draft: &mut DiphthongDraft) {
+    let row_height = FONT_ID.size;
+    let row_height = row_height + ui.style().spacing.item_spacing.y * 2.;
+    let row_height = row_height + ui.style().spacing.button_padding.y * 2.;
+
+    let mut removal = None;
+
+    let locked = state.lexicon_phonemes();
+
+    ui.push_id("language-pane-diphthongs", |ui| {
+        egui_extras::TableBuilder::new(ui)
+            .column(Column::auto().at_least(FONT_ID.size * 2.))
+            .columns(Column::remainder(), 2)
+            .column(Column::auto())
+            .header(row_height, |mut header| {
+                header.col(|ui| { ui.strong("Diphthong"); });
+                header.col(|ui| { ui.strong("Start"); });
+                header.col(|ui| { ui.strong("End"); });
+                header.col(|_| { /*  */ });
+            })
+            .body(|mut body| {
+                for id in state.dialects[state.inventory].diphthongs.iter() {
+                    let phoneme = &state.phonemes[*id];
+
+                    let (start, end) = match phoneme.phone {
+                        Phone::Diphthong { start, end } => (start, end),
+                        _ => unreachable!()
+                    };
+
+                    body.row(row_height, |mut row| {
+                        row.col(|ui| {
+                            let quality = phoneme.phone.glide().map(|[start, _]| start);
+
+                            util::draw_phoneme_cell(ui, phoneme, quality);
+                        });
+
+                        row.col(|ui| { ui.label(describe(start)); });
+                        row.col(|ui| { ui.label(describe(end)); });
+
+                        row.col(|ui| {
+                            let remove = egui::Button::new("Remove").small();
+
+                            let response = ui.add_enabled(!locked.contains(id), remove)
+                                .on_disabled_hover_text("Words of the lexicon are written with this diphthong");
+
+                            if response.clicked() {
+                                let _ = removal.insert(*id);
+                            }
+                        });
+                    });
+                }
+            });
+    });
+
+    if let Some(id) = removal {
+        state.remove_phoneme(state.inventory_index, id);
+    }
+
+    let mut vowels = state.ipa.vowels
+        .phoneme_qualities()
+        .map(|(id, PhonemeQuality(a, b, c))| {
+            ((a[0].into_usize(), b[0].into_usize(), c[0].into_usize()), id)
+        })
+        .collect::<Vec<_>>();
+
+    vowels.sort();
+
+    let vowels = vowels
+        .into_iter()
+        .map(|(_, id)| (id, format!("{}", state.phonemes[id])))
+        .collect::<Vec<_>>();
+
+    ui.horizontal(|ui| {
+        vowel_selector(ui, "language-pane-diphthong-start", &vowels, &mut draft.start);
+
+        ui.label("→");
+
+        vowel_selector(ui, "language-pane-diphthong-end", &vowels, &mut draft.end);
+
+        let (start, end) = match (draft.start, draft.end) {
+            (Some(start), Some(end)) if start != end => (start, end),
+            _ => {
+                ui.add_enabled(false, egui::Button::new("Add Diphthong"));
+                return;
+            }
+        };
+
+        let symbol = format!("{}{}", state.phonemes[start], state.phonemes[end]);
+
+        let exists = state.dialects[state.inventory].diphthongs
+            .iter()
+            .any(|id| state.phonemes[*id].symbol == symbol);
+
+        if ui.add_enabled(!exists, egui::Button::new("Add Diphthong")).clicked() {
+            state.add_diphthong(state.inventory_index, start, end);
+
+            *draft = DiphthongDraft::default();
+        }
+    });
+}
@@ -10,11 +10,13 @@ use crate::pane;
 
 use crate::pane::context::Context;
 use crate::state::Selection;
-use crate::types::category::{Outer, Inner, Pair, CategoryColor};
+use crate::types::category::{Outer, Inner, Pair};
 use crate::types::{Alphabet, Phoneme, Language};
 use crate::pane::language::LanguagePaneRole;
 use crate::pane::util;
+use crate::pane::util::CategoryColor;
 
+#[allow(clippy::too_many_arguments)]
 fn cell_populated<A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor>(
     windowed: bool,
     ui: &mut egui::Ui,
@@ -27,7 +29,7 @@ fn cell_populated<A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor>(
 
     let inventory: &Alphabet<A, B, C> = match role {
         InventoryPaneRole::Source { phonemes, .. } => phonemes,
-        InventoryPaneRole::Display { inventory } => inventory,
+        InventoryPaneRole::Display { inventory, .. } => inventory,
     };
 
     let quality = inventory.get_quality(phoneme.id());
@@ -71,7 +73,7 @@ fn cell_populated<A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor>(
 
             (response, quality, LanguagePaneRole::Ipa)
         },
-        InventoryPaneRole::Display { inventory } => {
+        InventoryPaneRole::Display { inventory, locked, removal } => {
              // TODO: I think this unwrap is safe, should double check
             let quality = inventory.get_quality(phoneme.id()).unwrap();
 
@@ -96,7 +98,12 @@ fn cell_populated<A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor>(
                 // TODO: Double check this unwrap
                 let quality = inventory.get_quality(phoneme.id()).unwrap();
                 
-                let context = Context::Bound { inventory, id: phoneme.id() };
+                let context = Context::Bound { 
+                    inventory, 
+                    id: phoneme.id(), 
+                    removable: !locked.contains(&phoneme.id()),
+                    removal
+                };
                 
                 pane::context::cell_context::<A, B, C>(ui, ipa, phonemes, context);
             });
@@ -116,6 +123,7 @@ fn cell_populated<A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor>(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cell<A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor>(
     windowed: bool,
     strip: &mut Strip<'_, '_>, 
@@ -142,16 +150,26 @@ pub enum InventoryPaneRole<'a: 'b, 'b: 'a, A: Outer<B, C>, B: Inner<C>, C: Pair>
         phonemes: &'b Alphabet<A, B, C>
     },
     // Reads from inventory
-    Display { inventory: &'a mut Alphabet<A, B, C> }
+    // Phonemes that are `locked` can't be removed, see `State::lexicon_phonemes`
+    // The phoneme chosen for removal is left in `removal`, see `State::remove_phoneme`
+    Display { 
+        inventory: &'a mut Alphabet<A, B, C>,
+        locked: &'b HashSet<slotmap::DefaultKey>,
+        removal: &'b mut Option<slotmap::DefaultKey>
+    }
 }
 
 pub struct InventoryPane<'a, 'b, A: Outer<B, C>, B: Inner<C>, C: Pair> {
-    pub role: InventoryPaneRole<'a, 'b, A, B, C>
+    pub role: InventoryPaneRole<'a, 'b, A, B, C>,
+    // The rows and columns of the chart to display, in order
+    pub rows: Vec<A>,
+    pub columns: Vec<B>
 }
 
 impl<'a, 'b, A, B, C> InventoryPane<'a, 'b, A, B, C>
     where A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor {
 
+    #[allow(clippy::too_many_arguments)]
     pub fn display(
         &mut self, 
         windowed: bool,
@@ -176,7 +194,7 @@ impl<'a, 'b, A, B, C> InventoryPane<'a, 'b, A, B, C>
             spacing.item_spacing.x = 0.;
         }
 
-        let cell_column_count = cardinality::<B>() * cardinality::<C>();
+        let cell_column_count = self.columns.len() * cardinality::<C>();
 
         let cell_proportion = (cell_column_count as f32).recip();
 
@@ -191,13 +209,13 @@ impl<'a, 'b, A, B, C> InventoryPane<'a, 'b, A, B, C>
         };
 
         StripBuilder::new(ui)
-            .sizes(size, cardinality::<A>() + 1)
+            .sizes(size, self.rows.len() + 1)
             .vertical(|mut strip| {
                 strip.strip(|builder| {
                     builder
-                        .sizes(Size::remainder(), cardinality::<B>())
+                        .sizes(Size::remainder(), self.columns.len())
                         .horizontal(|mut strip| {
-                            for b in all::<B>() {
+                            for b in self.columns.iter() {
                                 strip.cell(|ui| {
                                     ui.label(format!("{}", b));
                                 });
@@ -205,14 +223,14 @@ impl<'a, 'b, A, B, C> InventoryPane<'a, 'b, A, B, C>
                         });
                 });
 
-                for a in all::<A>() {
+                for a in self.rows.iter().copied() {
                     let occurrences = {
                         let inventory: &Alphabet<A, B, C> = match &self.role {
                             InventoryPaneRole::Source { phonemes, .. } => phonemes,
-                            InventoryPaneRole::Display { inventory } => inventory,
+                            InventoryPaneRole::Display { inventory, .. } => inventory,
                         };
 
-                        occurrences(invalid.clone(), space.clone(), phonemes, inventory, a)
+                        occurrences(invalid.clone(), space.clone(), phonemes, inventory, a, &self.columns)
                     };
 
                     strip.strip(|mut builder| {
@@ -304,12 +322,13 @@ fn occurrences<A: Outer<B, C>, B: Inner<C>, C: Pair>(
     space: Phoneme,
     phonemes: &SlotMap<slotmap::DefaultKey, Phoneme>, 
     alphabet: &Alphabet<A, B, C>, 
-    a: A) -> Vec<(Option<Phoneme>, usize)> {
+    a: A,
+    columns: &[B]) -> Vec<(Option<Phoneme>, usize)> {
     
     let mut sounds = HashSet::new();
-    let mut symbol_chart = String::with_capacity(cardinality::<B>() * cardinality::<C>());
+    let mut symbol_chart = String::with_capacity(columns.len() * cardinality::<C>());
 
-    for b in all::<B>() {
+    for b in columns.iter().copied() {
         for c in all::<C>() {
             let id = alphabet.get((a, b, c));
 
@@ -1,12 +1,15 @@
 mod inventory;
+mod diphthongs;
+mod tones;
 
 use std::rc;
 
 use egui_extras::Size;
-use enum_iterator::cardinality;
+use enum_iterator::{cardinality, all};
 
 use crate::app::FONT_ID;
 use crate::pane::Pane;
+use crate::pane::util;
 
 use crate::types::category::{
     Articulation,
@@ -23,14 +26,44 @@ pub enum LanguagePaneRole {
     Ipa
 }
 
+/// The consonant chart that is currently displayed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ConsonantChart {
+    Pulmonic,
+    NonPulmonic,
+    Other
+}
+
+impl ConsonantChart {
+    fn rows(&self) -> Vec<Articulation> {
+        all::<Articulation>()
+            .filter(|articulation| {
+                articulation.is_pulmonic() != matches!(self, ConsonantChart::NonPulmonic)
+            })
+            .collect()
+    }
+
+    fn columns(&self) -> Vec<Region> {
+        all::<Region>()
+            .filter(|region| region.is_other() == matches!(self, ConsonantChart::Other))
+            .collect()
+    }
+}
+
 pub struct LanguagePane {
-    role: LanguagePaneRole
+    role: LanguagePaneRole,
+    chart: ConsonantChart,
+    diphthong: diphthongs::DiphthongDraft,
+    tone: tones::ToneDraft
 }
 
 impl LanguagePane {
     pub fn new(role: LanguagePaneRole) -> Self {
         Self {
-            role
+            role,
+            chart: ConsonantChart::Pulmonic,
+            diphthong: diphthongs::DiphthongDraft::default(),
+            tone: tones::ToneDraft::default()
         }
     }
 }
@@ -48,12 +81,12 @@ impl Pane for LanguagePane {
         let spacing = ctx.style().spacing.item_spacing;
         let padding = ctx.style().spacing.button_padding;
 
-        let width = cardinality::<Region>() * cardinality::<Voicing>();
+        let width = ConsonantChart::Pulmonic.columns().len() * cardinality::<Voicing>();
         let width = cardinality::<Place>() * cardinality::<Rounding>() + width;
         let width = (FONT_ID.size + spacing.x) * width as f32;
 
-        let height = cardinality::<Articulation>().max(cardinality::<Constriction>());
-        let height = (FONT_ID.size + (spacing.y + padding.y) * 2.) * (height + 2) as f32;
+        let height = ConsonantChart::Pulmonic.rows().len().max(cardinality::<Constriction>());
+        let height = (FONT_ID.size + (spacing.y + padding.y) * 2.) * (height + 3) as f32;
         
         egui::Window::new(self.title(state).as_ref())
             .resizable(true)
@@ -63,17 +96,55 @@ impl Pane for LanguagePane {
     }
 
     fn show(&mut self, windowed: bool, state: &mut crate::State, ui: &mut egui::Ui) {    
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.chart, ConsonantChart::Pulmonic, "Pulmonic");
+            ui.selectable_value(&mut self.chart, ConsonantChart::NonPulmonic, "Non-Pulmonic");
+            ui.selectable_value(&mut self.chart, ConsonantChart::Other, "Other Symbols");
+        });
+
+        if matches!(self.role, LanguagePaneRole::Inventory) {
+            egui::TopBottomPanel::bottom(util::new_id())
+                .show_inside(ui, |ui| {
+                    ui.columns(2, |columns| {
+                        diphthongs::show_diphthongs(&mut columns[0], state, &mut self.diphthong);
+                        tones::show_tones(&mut columns[1], state, &mut self.tone);
+                    });
+                });
+        }
+
+        let (articulations, regions) = (self.chart.rows(), self.chart.columns());
+
+        let constrictions = all::<Constriction>().collect::<Vec<_>>();
+        let places = all::<Place>().collect::<Vec<_>>();
+
+        let columns_c = (regions.len() * cardinality::<Voicing>()) as f32;
+        let columns_v = (places.len() * cardinality::<Rounding>()) as f32;
+
+        let locked = state.lexicon_phonemes();
+
+        let (mut consonant_removal, mut vowel_removal) = (None, None);
+
         let inventory = &mut state.dialects[state.inventory];    
         let (mut consonants, mut vowels) = match self.role {
             LanguagePaneRole::Inventory => {
                 let consonants = inventory::InventoryPane {
                     role: inventory::InventoryPaneRole::Display { 
-                        inventory: &mut inventory.consonants }
+                        inventory: &mut inventory.consonants, 
+                        locked: &locked,
+                        removal: &mut consonant_removal
+                    },
+                    rows: articulations.clone(),
+                    columns: regions.clone()
                 };
 
                 let vowels = inventory::InventoryPane {
                     role: inventory::InventoryPaneRole::Display { 
-                        inventory: &mut inventory.vowels }
+                        inventory: &mut inventory.vowels, 
+                        locked: &locked,
+                        removal: &mut vowel_removal
+                    },
+                    rows: constrictions.clone(),
+                    columns: places.clone()
                 };
 
                 (consonants, vowels)
@@ -83,23 +154,24 @@ impl Pane for LanguagePane {
                     role: inventory::InventoryPaneRole::Source { 
                         inventory: &mut inventory.consonants, 
                         phonemes: &state.ipa.consonants 
-                    }
+                    },
+                    rows: articulations.clone(),
+                    columns: regions.clone()
                 };
 
                 let vowels = inventory::InventoryPane {
                     role: inventory::InventoryPaneRole::Source { 
                         inventory: &mut inventory.vowels, 
                         phonemes: &state.ipa.vowels
-                    }
+                    },
+                    rows: constrictions.clone(),
+                    columns: places.clone()
                 };
 
                 (consonants, vowels)
             }
         };
 
-        let columns_c = (cardinality::<Region>() * cardinality::<Voicing>()) as f32;
-        let columns_v = (cardinality::<Place>() * cardinality::<Rounding>()) as f32;
-
         let proportion = columns_c / (columns_c + columns_v);
 
         egui_extras::StripBuilder::new(ui)
@@ -131,7 +203,11 @@ impl Pane for LanguagePane {
                         &state.ipa
                     );
                 })
-            });        
+            });
+
+        for id in consonant_removal.into_iter().chain(vowel_removal) {
+            state.remove_phoneme(state.inventory_index, id);
+        }
     }
 
     fn on_dialect_change(&mut self, _state: &mut crate::State) { /* */ }
@@ -0,0 +1,87 @@
+use egui_extras::Column;
+
+use crate::app::FONT_ID;
+use crate::types::{Contour, Tone};
+
+/// A tone that has yet to be added, with its contour written as levels
+#[derive(Default)]
+pub struct ToneDraft {
+    name: String,
+    levels: String
+}
+
+pub fn show_tones(ui: &mut egui::Ui, state: &mut crate::State, draft: &mut ToneDraft) {
+    let row_height = FONT_ID.size;
+    let row_height = row_height + ui.style().spacing.item_spacing.y * 2.;
+    let row_height = row_height + ui.style().spacing.button_padding.y * 2.;
+
+    let tones = &mut state.dialects[state.inventory].tones;
+
+    let mut removal = None;
+
+    ui.push_id("language-pane-tones", |ui| {
+        egui_extras::TableBuilder::new(ui)
+            .column(Column::remainder())
+            .column(Column::auto().at_least(FONT_ID.size * 2.))
+            .column(Column::auto())
+            .column(Column::auto())
+            .header(row_height, |mut header| {
+                header.col(|ui| { ui.strong("Tone"); });
+                header.col(|ui| { ui.strong("Contour"); });
+                header.col(|ui| { ui.strong("Levels"); });
+                header.col(|_| { /*  */ });
+            })
+            .body(|mut body| {
+                for (idx, tone) in tones.iter_mut().enumerate() {
+                    body.row(row_height, |mut row| {
+                        row.col(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut tone.name)
+                                .desired_width(FONT_ID.size * 4.));
+                        });
+
+                        row.col(|ui| {
+                            let content = egui::RichText::new(format!("{}", tone.contour))
+                                .font(FONT_ID.to_owned());
+
+                            ui.label(content);
+                        });
+
+                        row.col(|ui| { ui.label(tone.contour.levels()); });
+
+                        row.col(|ui| {
+                            if ui.small_button("Remove").clicked() {
+                                let _ = removal.insert(idx);
+                            }
+                        });
+                    });
+                }
+            });
+    });
+
+    if let Some(idx) = removal {
+        tones.remove(idx);
+    }
+
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut draft.name)
+            .hint_text("Name")
+            .desired_width(FONT_ID.size * 4.));
+
+        ui.add(egui::TextEdit::singleline(&mut draft.levels)
+            .hint_text("Levels, e.g. 51")
+            .desired_width(FONT_ID.size * 4.));
+
+        let contour = Contour::from_levels(&draft.levels)
+            .filter(|contour| !tones.iter().any(|tone| tone.contour == *contour));
+
+        if let Some(contour) = &contour {
+            ui.label(egui::RichText::new(format!("{}", contour)).font(FONT_ID.to_owned()));
+        }
+
+        if ui.add_enabled(contour.is_some(), egui::Button::new("Add Tone")).clicked() {
+            let ToneDraft { name, .. } = std::mem::take(draft);
+
+            tones.push(Tone { name, contour: contour.unwrap() });
+        }
+    });
+}
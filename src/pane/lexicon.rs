@@ -1,19 +1,557 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+use egui::RichText;
+use egui_extras::Column;
+use enum_iterator::all;
+use slotmap::DefaultKey;
+
+use crate::app::FONT_ID;
 use crate::pane::Pane;
+use crate::types::{Entry, EntryKey, PartOfSpeech, Word, Violation, Prosody};
+use crate::types::{word_to_string, is_valid_word, parse_prosodic_word, prosodic_word_to_string};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LexiconColumn {
+    Form,
+    Romanization,
+    Gloss,
+    PartOfSpeech
+}
+
+impl LexiconColumn {
+    fn title(&self) -> &'static str {
+        match self {
+            LexiconColumn::Form => "Form",
+            LexiconColumn::Romanization => "Romanization",
+            LexiconColumn::Gloss => "Gloss",
+            LexiconColumn::PartOfSpeech => "Part of Speech"
+        }
+    }
+}
+
+/// An entry as it's being typed, before its form has been parsed
+#[derive(Default)]
+struct Draft {
+    form: String,
+    gloss: String,
+    part_of_speech: PartOfSpeech,
+    notes: String
+}
+
+/// Writes a form through the language's orthography if `romanized`, otherwise as IPA
+fn write_form(state: &crate::State, form: &[DefaultKey], prosody: &Prosody, romanized: bool) -> String {
+    if romanized {
+        let lineage = state.lineage(state.inventory_index);
+
+        state.orthography(state.inventory_index).romanize(&lineage, form)
+    } else {
+        prosodic_word_to_string(&state.phonemes, form, prosody)
+    }
+}
+
+impl Draft {
+    fn from_entry(state: &crate::State, entry: &Entry, romanized: bool) -> Self {
+        Self {
+            form: write_form(state, &entry.form, &entry.prosody, romanized),
+            gloss: entry.gloss.clone(),
+            part_of_speech: entry.part_of_speech,
+            notes: entry.notes.clone()
+        }
+    }
+
+    /// The form rewritten for when forms are read the other way, see `parse_form`
+    /// A blank form is left as it is
+    fn respelled(&self, state: &crate::State, romanized: bool) -> Result<String, String> {
+        if self.form.trim().is_empty() {
+            return Ok(self.form.clone());
+        }
+
+        let (form, prosody, _) = self.parse_form(state, !romanized)?;
+
+        Ok(write_form(state, &form, &prosody.unwrap_or_default(), romanized))
+    }
+
+    fn into_entry(self, form: Word, prosody: Prosody) -> Entry {
+        let Self { gloss, part_of_speech, notes, .. } = self;
+
+        Entry { form, prosody, gloss, part_of_speech, notes }
+    }
+
+    /// Reads the form as IPA, or through the language's orthography if `romanized`
+    /// Spellings don't record stress or tone, so the prosody is only read from IPA
+    /// Any ambiguities in the spelling or unknown tones are returned as warnings
+    fn parse_form(&self, state: &crate::State, romanized: bool) -> Result<(Word, Option<Prosody>, Vec<String>), String> {
+        let parsed = if romanized {
+            let lineage = state.lineage(state.inventory_index);
+
+            state.orthography(state.inventory_index)
+                .deromanize(&lineage, &self.form)
+                .map(|deromanization| {
+                    let warnings = deromanization.ambiguities
+                        .iter()
+                        .map(|ambiguity| format!("{}", ambiguity))
+                        .collect::<Vec<_>>();
+
+                    (deromanization.word, None, warnings)
+                })
+                .map_err(|error| format!("{}", error))?
+        } else {
+            let lineage = state.inventory_lineage(state.inventory_index);
+
+            let (word, prosody) = parse_prosodic_word(&self.form, &lineage)
+                .ok_or_else(|| String::from("Form must be written with phonemes from the current language"))?;
+
+            let warnings = prosody.0
+                .values()
+                .filter_map(|syllable| syllable.tone.as_ref())
+                .filter(|tone| !lineage.tones().any(|known| known.contour == **tone))
+                .map(|tone| format!("Tone {} isn't in the tone inventory", tone))
+                .collect::<Vec<_>>();
+
+            (word, Some(prosody), warnings)
+        };
+
+        match parsed {
+            (word, ..) if word.is_empty() => Err(String::from("Form can't be empty")),
+            parsed => Ok(parsed)
+        }
+    }
+}
+
+fn part_of_speech_selector(ui: &mut egui::Ui, id: &str, part_of_speech: &mut PartOfSpeech) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(format!("{}", part_of_speech))
+        .show_ui(ui, |ui| {
+            for variant in all::<PartOfSpeech>() {
+                ui.selectable_value(part_of_speech, variant, format!("{}", variant));
+            }
+        });
+}
+
+/// The romanized forms of the lexicon, kept until the spellings they were written with change
+#[derive(Default)]
+struct Romanizations {
+    spellings: u64,
+    forms: HashMap<Word, String>
+}
+
+impl Romanizations {
+    /// Romanizes the forms of the current dialect that haven't been yet
+    fn update(&mut self, state: &crate::State) {
+        let lineage = state.lineage(state.inventory_index);
+
+        // Contextual spellings depend on the qualities of the phonemes around them as well as on the spellings
+        let mut hasher = DefaultHasher::new();
+        for ancestor in state.ancestors(state.inventory_index) {
+            for id in state.dialects[state.language_tree[ancestor]].phonemes() {
+                id.hash(&mut hasher);
+                state.phonemes.get(id).hash(&mut hasher);
+                lineage.consonant_quality(id).hash(&mut hasher);
+                lineage.vowel_quality(id).hash(&mut hasher);
+            }
+        }
+
+        let spellings = hasher.finish();
+        if spellings != self.spellings {
+            self.spellings = spellings;
+            self.forms.clear();
+        }
+
+        let orthography = state.orthography(state.inventory_index);
+
+        for entry in state.dialects[state.inventory].lexicon.values() {
+            if !self.forms.contains_key(&entry.form) {
+                let _ = self.forms.insert(entry.form.clone(), orthography.romanize(&lineage, &entry.form));
+            }
+        }
+    }
+
+    fn get(&self, form: &Word) -> &str {
+        self.forms.get(form).map_or("", String::as_str)
+    }
+}
+
+pub struct LexiconPane {
+    draft: Draft,
+    editing: Option<(EntryKey, Draft)>,
+    filter: String,
+    filter_part_of_speech: Option<PartOfSpeech>,
+    sort: (LexiconColumn, bool),
+    romanized: bool,
+    romanizations: Romanizations,
+    error: Option<String>,
+    warnings: Vec<String>
+}
+
+impl LexiconPane {
+    pub fn new() -> Self {
+        Self {
+            draft: Draft::default(),
+            editing: None,
+            filter: String::new(),
+            filter_part_of_speech: None,
+            sort: (LexiconColumn::Gloss, true),
+            romanized: false,
+            romanizations: Romanizations::default(),
+            error: None,
+            warnings: Vec::new()
+        }
+    }
+
+    fn show_draft_editor(&mut self, state: &mut crate::State, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Form");
+            ui.add(egui::TextEdit::singleline(&mut self.draft.form)
+                .font(FONT_ID.to_owned())
+                .desired_width(FONT_ID.size * 6.));
+
+            ui.label("Gloss");
+            ui.add(egui::TextEdit::singleline(&mut self.draft.gloss)
+                .desired_width(FONT_ID.size * 6.));
+
+            part_of_speech_selector(ui, "lexicon-pane-draft-pos", &mut self.draft.part_of_speech);
+
+            ui.label("Notes");
+            ui.add(egui::TextEdit::singleline(&mut self.draft.notes)
+                .desired_width(FONT_ID.size * 8.));
+
+            let toggled = ui.checkbox(&mut self.romanized, "Romanized")
+                .on_hover_text("Read forms through the language's orthography instead of as IPA")
+                .changed();
+
+            if toggled {
+                self.respell_drafts(state);
+            }
+
+            if ui.button("Add").clicked() {
+                match self.draft.parse_form(state, self.romanized) {
+                    Ok((form, prosody, warnings)) => {
+                        let draft = mem::take(&mut self.draft);
+                        let prosody = prosody.unwrap_or_default();
+
+                        state.dialects[state.inventory].lexicon.insert(draft.into_entry(form, prosody));
+
+                        self.error = None;
+                        self.warnings = warnings;
+                    },
+                    Err(error) => {
+                        self.error = Some(error);
+                        self.warnings.clear();
+                    }
+                }
+            }
+        });
+
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        for warning in self.warnings.iter() {
+            ui.colored_label(ui.visuals().warn_fg_color, warning);
+        }
+    }
+
+    /// Rewrites the forms being typed after `romanized` was toggled, so that they're read the new way
+    /// If either can't be read the old way, the toggle is undone instead
+    fn respell_drafts(&mut self, state: &crate::State) {
+        let respelled = self.draft
+            .respelled(state, self.romanized)
+            .and_then(|form| match &self.editing {
+                Some((_, draft)) => draft.respelled(state, self.romanized).map(|edited| (form, Some(edited))),
+                None => Ok((form, None))
+            });
+
+        match respelled {
+            Ok((form, edited)) => {
+                self.draft.form = form;
+
+                if let (Some((_, draft)), Some(edited)) = (&mut self.editing, edited) {
+                    draft.form = edited;
+                }
 
-pub struct LexiconPane;
+                self.error = None;
+            },
+            Err(error) => {
+                self.romanized = !self.romanized;
+                self.error = Some(format!("Unable to switch how forms are read: {}", error));
+            }
+        }
+    }
+
+    fn show_filter(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Filter");
+            ui.text_edit_singleline(&mut self.filter);
+
+            egui::ComboBox::from_id_source("lexicon-pane-filter-pos")
+                .selected_text(match self.filter_part_of_speech {
+                    Some(part_of_speech) => format!("{}", part_of_speech),
+                    None => String::from("Any")
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.filter_part_of_speech, None, "Any");
+
+                    for variant in all::<PartOfSpeech>() {
+                        ui.selectable_value(&mut self.filter_part_of_speech, Some(variant), format!("{}", variant));
+                    }
+                });
+        });
+    }
+
+    /// The keys of every entry that passes the filter, in sorted order
+    fn entries(&self, state: &crate::State) -> Vec<EntryKey> {
+        let lexicon = &state.dialects[state.inventory].lexicon;
+
+        let filter = self.filter.to_lowercase();
+
+        let mut entries = lexicon
+            .iter()
+            .filter(|(_, entry)| match self.filter_part_of_speech {
+                Some(part_of_speech) => entry.part_of_speech == part_of_speech,
+                None => true
+            })
+            .filter(|(_, entry)| {
+                filter.is_empty() //
+                    || entry.gloss.to_lowercase().contains(&filter) //
+                    || entry.notes.to_lowercase().contains(&filter) //
+                    || word_to_string(&state.phonemes, &entry.form).contains(&filter) //
+                    || self.romanizations.get(&entry.form).to_lowercase().contains(&filter)
+            })
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+
+        let (column, ascending) = self.sort;
+
+        entries.sort_by(|a, b| {
+            let (a, b) = (&lexicon[*a], &lexicon[*b]);
+
+            let ordering = match column {
+                LexiconColumn::Form => word_to_string(&state.phonemes, &a.form)
+                    .cmp(&word_to_string(&state.phonemes, &b.form)),
+                LexiconColumn::Romanization => self.romanizations.get(&a.form)
+                    .cmp(self.romanizations.get(&b.form)),
+                LexiconColumn::Gloss => a.gloss.to_lowercase().cmp(&b.gloss.to_lowercase()),
+                LexiconColumn::PartOfSpeech => a.part_of_speech.cmp(&b.part_of_speech)
+            };
+
+            if ascending { ordering } else { ordering.reverse() }
+        });
+
+        entries
+    }
+}
+
+enum LexiconAction {
+    Edit(EntryKey),
+    Save,
+    Cancel,
+    Delete(EntryKey)
+}
 
 impl Pane for LexiconPane {
     fn title(&self, _state: &crate::State) -> std::rc::Rc<str> {
         std::rc::Rc::from("Lexicon")
     }
-    
+
     fn setup<'a, 'b: 'a>(&'a mut self, state: &crate::State, _ctx: &egui::Context) -> egui::Window<'b> {
         egui::Window::new(self.title(state).as_ref())
     }
 
-    fn show(&mut self, _windowed: bool, _state: &mut crate::State, _ui: &mut egui::Ui) {
-        
+    fn show(&mut self, _windowed: bool, state: &mut crate::State, ui: &mut egui::Ui) {
+        self.show_draft_editor(state, ui);
+
+        ui.separator();
+
+        self.show_filter(ui);
+
+        self.romanizations.update(state);
+
+        let entries = self.entries(state);
+
+        let row_height = FONT_ID.size;
+        let row_height = row_height + ui.style().spacing.item_spacing.y * 2.;
+        let row_height = row_height + ui.style().spacing.button_padding.y * 2.;
+
+        let mut action = None;
+
+        let lineage = state.lineage(state.inventory_index);
+
+        let phonotactics = &state.dialects[state.inventory].phonotactics;
+
+        let romanizations = &self.romanizations;
+
+        egui_extras::TableBuilder::new(ui)
+            .striped(true)
+            .columns(Column::remainder(), 5)
+            .column(Column::auto())
+            .vscroll(true)
+            .header(row_height, |mut header| {
+                for column in [
+                    LexiconColumn::Form,
+                    LexiconColumn::Romanization,
+                    LexiconColumn::Gloss,
+                    LexiconColumn::PartOfSpeech] {
+
+                    header.col(|ui| {
+                        let (sorted_by, ascending) = self.sort;
+
+                        let content = match (sorted_by == column, ascending) {
+                            (true, true) => format!("{} ⏶", column.title()),
+                            (true, false) => format!("{} ⏷", column.title()),
+                            (false, _) => String::from(column.title())
+                        };
+
+                        if ui.button(RichText::new(content).strong()).clicked() {
+                            self.sort = (column, sorted_by != column || !ascending);
+                        }
+                    });
+                }
+
+                header.col(|ui| { ui.strong("Notes"); });
+                header.col(|_| { /*  */ });
+            })
+            .body(|mut body| {
+                for key in entries {
+                    body.row(row_height, |mut row| {
+                        match &mut self.editing {
+                            Some((editing, draft)) if *editing == key => {
+                                row.col(|ui| {
+                                    ui.add(egui::TextEdit::singleline(&mut draft.form)
+                                        .font(FONT_ID.to_owned()));
+                                });
+
+                                row.col(|_| { /*  */ });
+
+                                row.col(|ui| { ui.text_edit_singleline(&mut draft.gloss); });
+
+                                row.col(|ui| {
+                                    part_of_speech_selector(ui, "lexicon-pane-edit-pos", &mut draft.part_of_speech);
+                                });
+
+                                row.col(|ui| { ui.text_edit_singleline(&mut draft.notes); });
+
+                                row.col(|ui| {
+                                    if ui.button("Save").clicked() {
+                                        let _ = action.insert(LexiconAction::Save);
+                                    }
+
+                                    if ui.button("Cancel").clicked() {
+                                        let _ = action.insert(LexiconAction::Cancel);
+                                    }
+                                });
+                            },
+                            _ => {
+                                let entry = &state.dialects[state.inventory].lexicon[key];
+
+                                row.col(|ui| {
+                                    let content = prosodic_word_to_string(&state.phonemes, &entry.form, &entry.prosody);
+                                    let content = RichText::new(format!("/{}/", content))
+                                        .font(FONT_ID.to_owned());
+
+                                    ui.label(content);
+
+                                    let violations = phonotactics.validate(&lineage, &entry.form);
+
+                                    if !is_valid_word(&state.phonemes, &entry.form) {
+                                        ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                                            .on_hover_text("Written with phonemes that were removed, shown as '?'");
+                                    } else if !violations.is_empty() {
+                                        let description = violations
+                                            .iter()
+                                            .map(|violation| match violation {
+                                                Violation::Syllabification => 
+                                                    String::from("Doesn't follow any of the syllable templates"),
+                                                Violation::BannedCluster(cluster) => 
+                                                    format!("Contains the banned cluster /{}/", word_to_string(&state.phonemes, cluster))
+                                            })
+                                            .collect::<Vec<_>>()
+                                            .join("\n");
+
+                                        ui.colored_label(ui.visuals().warn_fg_color, "⚠")
+                                            .on_hover_text(description);
+                                    }
+                                });
+
+                                row.col(|ui| {
+                                    let content = RichText::new(romanizations.get(&entry.form))
+                                        .font(FONT_ID.to_owned())
+                                        .italics();
+
+                                    ui.label(content);
+                                });
+
+                                row.col(|ui| { ui.label(entry.gloss.as_str()); });
+                                row.col(|ui| { ui.label(format!("{}", entry.part_of_speech)); });
+                                row.col(|ui| { ui.label(entry.notes.as_str()); });
+
+                                row.col(|ui| {
+                                    if ui.button("Edit").clicked() {
+                                        let _ = action.insert(LexiconAction::Edit(key));
+                                    }
+
+                                    if ui.button("Delete").clicked() {
+                                        let _ = action.insert(LexiconAction::Delete(key));
+                                    }
+                                });
+                            }
+                        }
+                    });
+                }
+            });
+
+        match action {
+            Some(LexiconAction::Edit(key)) => {
+                let entry = &state.dialects[state.inventory].lexicon[key];
+
+                self.editing = Some((key, Draft::from_entry(state, entry, self.romanized)));
+            },
+            Some(LexiconAction::Save) => if let Some((key, draft)) = self.editing.take() {
+                match draft.parse_form(state, self.romanized) {
+                    Ok((form, prosody, warnings)) => {
+                        let lexicon = &mut state.dialects[state.inventory].lexicon;
+
+                        if let Some(entry) = lexicon.get_mut(key) {
+                            // Respelling a form keeps whatever stress and tone still fit it
+                            let prosody = prosody.unwrap_or_else(|| {
+                                let mut prosody = entry.prosody.clone();
+                                prosody.retain_nuclei(&state.phonemes, &form);
+                                prosody
+                            });
+
+                            *entry = draft.into_entry(form, prosody);
+                        }
+
+                        self.error = None;
+                        self.warnings = warnings;
+                    },
+                    Err(error) => {
+                        self.editing = Some((key, draft));
+                        self.error = Some(error);
+                        self.warnings.clear();
+                    }
+                }
+            },
+            Some(LexiconAction::Cancel) => {
+                self.editing = None;
+            },
+            Some(LexiconAction::Delete(key)) => {
+                state.dialects[state.inventory].lexicon.remove(key);
+
+                if matches!(self.editing, Some((editing, _)) if editing == key) {
+                    self.editing = None;
+                }
+            },
+            None => { /*  */ }
+        }
+    }
+
+    fn on_dialect_change(&mut self, _state: &mut crate::State) {
+        self.editing = None;
+        self.error = None;
+        self.warnings.clear();
     }
+}
 
-    fn on_dialect_change(&mut self, _state: &mut crate::State) { /* */ }
-}
\ No newline at end of file
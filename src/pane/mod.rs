@@ -3,6 +3,9 @@ mod romanization;
 mod lexicon;
 mod sound_change;
 mod dialect_view;
+mod cognates;
+mod phonotactics;
+mod generator;
 mod context;
 
 pub mod util;
@@ -16,6 +19,9 @@ use romanization::RomanizationPane;
 use lexicon::LexiconPane;
 use sound_change::SoundChangePane;
 use dialect_view::DialectPane;
+use cognates::CognatePane;
+use phonotactics::PhonotacticsPane;
+use generator::GeneratorPane;
 
 use enum_map::{Enum, EnumMap, enum_map};
 
@@ -28,7 +34,10 @@ pub enum PaneId {
     Romanization,
     Lexicon,
     SoundChange,
-    Dialects
+    Dialects,
+    Cognates,
+    Phonotactics,
+    Generator
 }
 
 pub trait Pane {
@@ -49,11 +58,11 @@ pub fn init_panes() -> EnumMap<PaneId, Box<dyn Pane>> {
             temp
         },
         PaneId::Romanization => {
-            let temp: Box<dyn Pane> = Box::new(RomanizationPane);
+            let temp: Box<dyn Pane> = Box::new(RomanizationPane::new());
             temp
         },
         PaneId::Lexicon => {
-            let temp: Box<dyn Pane> = Box::new(LexiconPane);
+            let temp: Box<dyn Pane> = Box::new(LexiconPane::new());
             temp
         },
         PaneId::SoundChange => {
@@ -63,6 +72,18 @@ pub fn init_panes() -> EnumMap<PaneId, Box<dyn Pane>> {
         PaneId::Dialects => {
             let temp: Box<dyn Pane> = Box::new(DialectPane::new());
             temp
+        },
+        PaneId::Cognates => {
+            let temp: Box<dyn Pane> = Box::new(CognatePane::new());
+            temp
+        },
+        PaneId::Phonotactics => {
+            let temp: Box<dyn Pane> = Box::new(PhonotacticsPane::new());
+            temp
+        },
+        PaneId::Generator => {
+            let temp: Box<dyn Pane> = Box::new(GeneratorPane::new());
+            temp
         }
     }
 }
\ No newline at end of file
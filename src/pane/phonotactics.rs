@@ -0,0 +1,198 @@
+use egui::RichText;
+
+use crate::app::FONT_ID;
+use crate::pane::Pane;
+use crate::pane::sound_change::environment;
+use crate::types::{SlotClass, SoundChangeContext, PhonemeSelector, Word};
+use crate::types::{parse_word, word_to_string};
+
+/// Text buffers for the parts of the phonotactics that are edited as text
+struct Buffers {
+    templates: String,
+    banned: String
+}
+
+impl Buffers {
+    fn new(state: &crate::State) -> Self {
+        let phonotactics = &state.dialects[state.inventory].phonotactics;
+
+        Self {
+            templates: phonotactics.templates
+                .iter()
+                .map(|template| format!("{}", template))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            banned: phonotactics.banned
+                .iter()
+                .map(|cluster| word_to_string(&state.phonemes, cluster))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+pub struct PhonotacticsPane {
+    buffers: Option<Buffers>,
+    symbol: String,
+    errors: Vec<String>
+}
+
+impl PhonotacticsPane {
+    pub fn new() -> Self {
+        Self {
+            buffers: None,
+            symbol: String::new(),
+            errors: Vec::new()
+        }
+    }
+
+    fn show_classes(&mut self, state: &mut crate::State, ui: &mut egui::Ui) {
+        ui.heading("Classes");
+
+        let classes = &mut state.dialects[state.inventory].phonotactics.classes;
+
+        let mut removal = None;
+        for (idx, SlotClass { symbol, context }) in classes.iter_mut().enumerate() {
+            ui.push_id(idx, |ui| ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("{} =", symbol)).font(FONT_ID.to_owned()));
+
+                environment::environment_editor(ui, &state.phonemes, context);
+
+                ui.separator();
+
+                if ui.button("Remove").clicked() {
+                    let _ = removal.insert(idx);
+                }
+            }));
+        }
+
+        if let Some(idx) = removal {
+            classes.remove(idx);
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.symbol)
+                .char_limit(1)
+                .desired_width(FONT_ID.size));
+
+            if ui.button("Add Class").clicked() {
+                match self.symbol.chars().next() {
+                    Some(symbol) if "()".contains(symbol) || symbol.is_whitespace() => { /*  */ },
+                    Some(symbol) if classes.iter().all(|class| class.symbol != symbol) => {
+                        let context = SoundChangeContext::Consonant(PhonemeSelector::blank());
+
+                        classes.push(SlotClass { symbol, context });
+
+                        self.symbol.clear();
+                    },
+                    _ => { /*  */ }
+                }
+            }
+        });
+    }
+
+    fn show_templates(&mut self, state: &mut crate::State, ui: &mut egui::Ui) {
+        let buffers = self.buffers.get_or_insert_with(|| Buffers::new(state));
+
+        ui.horizontal(|ui| {
+            ui.heading("Syllable Templates");
+
+            if ui.button("Apply").clicked() {
+                let phonotactics = &mut state.dialects[state.inventory].phonotactics;
+
+                let mut templates = Vec::new();
+
+                self.errors.clear();
+
+                for (idx, line) in buffers.templates.lines().enumerate() {
+                    if line.trim().is_empty() { continue; }
+
+                    match phonotactics.parse_template(line) {
+                        Ok(template) => templates.push(template),
+                        Err(error) => self.errors.push(format!("Line {}: {}", idx + 1, error))
+                    }
+                }
+
+                if self.errors.is_empty() {
+                    phonotactics.templates = templates;
+                }
+            }
+        });
+
+        ui.label("One template per line, such as '(C)(C)V(C)'");
+
+        ui.add(egui::TextEdit::multiline(&mut buffers.templates)
+            .font(FONT_ID.to_owned())
+            .desired_rows(3));
+    }
+
+    fn show_banned(&mut self, state: &mut crate::State, ui: &mut egui::Ui) {
+        let buffers = self.buffers.get_or_insert_with(|| Buffers::new(state));
+
+        ui.horizontal(|ui| {
+            ui.heading("Banned Clusters");
+
+            if ui.button("Apply").clicked() {
+                let mut banned: Vec<Word> = Vec::new();
+
+                self.errors.clear();
+
+                {
+                    let lineage = state.lineage(state.inventory_index);
+
+                    for (idx, line) in buffers.banned.lines().enumerate() {
+                        if line.trim().is_empty() { continue; }
+
+                        match parse_word(line, lineage.candidates()) {
+                            Some(cluster) => banned.push(cluster),
+                            None => self.errors.push(format!("Line {}: Unknown phoneme in '{}'", idx + 1, line.trim()))
+                        }
+                    }
+                }
+
+                if self.errors.is_empty() {
+                    state.dialects[state.inventory].phonotactics.banned = banned;
+                }
+            }
+        });
+
+        ui.label("One cluster per line, written in IPA");
+
+        ui.add(egui::TextEdit::multiline(&mut buffers.banned)
+            .font(FONT_ID.to_owned())
+            .desired_rows(3));
+    }
+}
+
+impl Pane for PhonotacticsPane {
+    fn title(&self, _state: &crate::State) -> std::rc::Rc<str> {
+        std::rc::Rc::from("Phonotactics")
+    }
+
+    fn setup<'a, 'b: 'a>(&'a mut self, state: &crate::State, _ctx: &egui::Context) -> egui::Window<'b> {
+        egui::Window::new(self.title(state).as_ref())
+    }
+
+    fn show(&mut self, _windowed: bool, state: &mut crate::State, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            self.show_classes(state, ui);
+
+            ui.separator();
+
+            self.show_templates(state, ui);
+
+            ui.separator();
+
+            self.show_banned(state, ui);
+
+            for error in self.errors.iter() {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        });
+    }
+
+    fn on_dialect_change(&mut self, _state: &mut crate::State) {
+        self.buffers = None;
+        self.errors.clear();
+    }
+}
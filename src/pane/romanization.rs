@@ -1,15 +1,25 @@
 use egui_extras::{Size, Column};
-use slotmap::SlotMap;
+use slotmap::{SlotMap, DefaultKey};
 
 use crate::pane::Pane;
 use crate::pane::util;
-use crate::types::PhonemeQuality;
-use crate::types::category::CategoryColor;
+use crate::pane::util::CategoryColor;
+use crate::pane::sound_change::environment::environment_editor;
+use crate::types::{PhonemeQuality, ContextualSpelling};
 use crate::types::category::{Outer, Inner, Pair};
-use crate::types::{Phoneme, Alphabet};
+use crate::types::{Phoneme, Alphabet, word_to_string};
 use crate::app::FONT_ID;
 
-pub struct RomanizationPane;
+pub struct RomanizationPane {
+    editing: Option<DefaultKey>,
+    test: String
+}
+
+impl RomanizationPane {
+    pub fn new() -> Self {
+        Self { editing: None, test: String::new() }
+    }
+}
 
 impl Pane for RomanizationPane {
     fn title(&self, _state: &crate::State) -> std::rc::Rc<str> {
@@ -21,6 +31,66 @@ impl Pane for RomanizationPane {
     }
 
     fn show(&mut self, _windowed: bool, state: &mut crate::State, ui: &mut egui::Ui) {
+        egui::TopBottomPanel::bottom(util::new_id())
+            .show_inside(ui, |ui| {
+
+            // Environments are written with the other phonemes, so the edited one is copied out while it's shown
+            if let Some(mut phoneme) = self.editing.and_then(|id| state.phonemes.get(id)).cloned() {
+                if show_spellings(ui, &state.phonemes, &mut phoneme) {
+                    let _ = self.editing.take();
+                }
+
+                let id = phoneme.id();
+                state.phonemes[id] = phoneme;
+
+                ui.separator();
+            }
+
+            let lineage = state.lineage(state.inventory_index);
+
+            let orthography = state.orthography(state.inventory_index);
+
+            for grapheme in orthography.shared_graphemes() {
+                ui.colored_label(ui.visuals().warn_fg_color, 
+                    format!("'{}' spells more than one phoneme", grapheme));
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Test spelling");
+
+                let test_editor = egui::TextEdit::singleline(&mut self.test)
+                    .font(FONT_ID.to_owned());
+
+                ui.add(test_editor);
+
+                if self.test.trim().is_empty() {
+                    return;
+                }
+
+                match orthography.deromanize(&lineage, self.test.trim()) {
+                    Ok(deromanization) => {
+                        let content = format!("→ {}", word_to_string(&state.phonemes, &deromanization.word));
+                        let content = egui::RichText::new(content)
+                            .font(FONT_ID.to_owned());
+
+                        ui.label(content);
+
+                        let respelled = orthography.romanize(&lineage, &deromanization.word);
+                        if respelled != self.test.trim() {
+                            ui.label(format!("(written '{}')", respelled));
+                        }
+
+                        for ambiguity in deromanization.ambiguities {
+                            ui.colored_label(ui.visuals().warn_fg_color, format!("{}", ambiguity));
+                        }
+                    },
+                    Err(error) => {
+                        ui.colored_label(ui.visuals().error_fg_color, format!("{}", error));
+                    }
+                }
+            });
+        });
+
         egui_extras::StripBuilder::new(ui) 
             .sizes(Size::remainder(), 2)
             .horizontal(|mut strip: egui_extras::Strip<'_, '_>| {
@@ -30,7 +100,8 @@ impl Pane for RomanizationPane {
                             ui.heading("Consonants");
                             show_graphemes(ui, 
                                 &state.dialects[state.inventory].consonants, 
-                                &mut state.phonemes);
+                                &mut state.phonemes,
+                                &mut self.editing);
                         });
                     });
                 });
@@ -41,7 +112,8 @@ impl Pane for RomanizationPane {
                             ui.heading("Vowels");
                             show_graphemes(ui, 
                                 &state.dialects[state.inventory].vowels, 
-                                &mut state.phonemes);
+                                &mut state.phonemes,
+                                &mut self.editing);
                         });
                         
                     });
@@ -49,13 +121,17 @@ impl Pane for RomanizationPane {
             });
     }
 
-    fn on_dialect_change(&mut self, _state: &mut crate::State) { /* */ }
+    fn on_dialect_change(&mut self, _state: &mut crate::State) {
+        let _ = self.editing.take();
+    }
 }
 
 fn show_grapheme_row<A, B, C>(
     mut row: egui_extras::TableRow<'_, '_>, 
+    id: DefaultKey,
     phoneme: &mut Phoneme, 
-    quality: PhonemeQuality<A, B, C>) where 
+    quality: PhonemeQuality<A, B, C>,
+    editing: &mut Option<DefaultKey>) where 
     A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor {
 
     row.col(|ui| {
@@ -81,12 +157,93 @@ fn show_grapheme_row<A, B, C>(
 
         ui.add(grapheme_editor);
     });
+
+    row.col(|ui| {
+        let content = match phoneme.spellings.len() {
+            0 => String::from("Contexts"),
+            count => format!("Contexts ({})", count)
+        };
+
+        if ui.selectable_label(*editing == Some(id), content).clicked() {
+            *editing = if *editing == Some(id) { None } else { Some(id) };
+        }
+    });
+}
+
+/// Edits the ordered list of contextual spellings for a single phoneme
+/// Returns true if the editor should be closed
+fn show_spellings(ui: &mut egui::Ui, phonemes: &SlotMap<DefaultKey, Phoneme>, phoneme: &mut Phoneme) -> bool {
+    let mut closed = false;
+
+    ui.horizontal(|ui| {
+        let content = egui::RichText::new(format!("/{}/", phoneme))
+            .font(FONT_ID.to_owned());
+
+        ui.label(content);
+        ui.label("is spelled with the first of these whose environment matches, otherwise with its grapheme");
+
+        closed = ui.button("Close").clicked();
+    });
+
+    let mut removal = None;
+    let mut promotion = None;
+
+    let count = phoneme.spellings.len();
+    for (idx, spelling) in phoneme.spellings.iter_mut().enumerate() {
+        ui.push_id(idx, |ui| {
+            ui.horizontal(|ui| {
+                let grapheme_editor = //
+                    egui::TextEdit::singleline(&mut spelling.grapheme)
+                        .font(FONT_ID.to_owned())
+                        .desired_width(FONT_ID.size * 4.);
+
+                ui.add(grapheme_editor);
+
+                ui.label("/");
+
+                let (before, after) = &mut spelling.context;
+
+                ui.push_id("before", |ui| environment_editor(ui, phonemes, before));
+                ui.label("_");
+                ui.push_id("after", |ui| environment_editor(ui, phonemes, after));
+
+                ui.separator();
+
+                if ui.add_enabled(idx > 0, egui::Button::new("⏶")).clicked() {
+                    let _ = promotion.insert(idx);
+                }
+
+                if ui.add_enabled(idx + 1 < count, egui::Button::new("⏷")).clicked() {
+                    let _ = promotion.insert(idx + 1);
+                }
+
+                if ui.button("Remove").clicked() {
+                    let _ = removal.insert(idx);
+                }
+            });
+        });
+    }
+
+    if let Some(idx) = promotion {
+        phoneme.spellings.swap(idx - 1, idx);
+    }
+
+    if let Some(idx) = removal {
+        phoneme.spellings.remove(idx);
+    }
+
+    if ui.button("Add Spelling").clicked() {
+        phoneme.spellings.push(ContextualSpelling::default());
+    }
+
+    closed
 }
 
 fn show_graphemes<A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor>(
     ui: &mut egui::Ui, 
     inventory: &Alphabet<A, B, C>,
-    phonemes: &mut SlotMap<slotmap::DefaultKey, Phoneme>) {
+    phonemes: &mut SlotMap<slotmap::DefaultKey, Phoneme>,
+    editing: &mut Option<DefaultKey>) {
 
     let row_height = FONT_ID.size;
     let row_height = row_height + ui.style().spacing.item_spacing.y * 4.;
@@ -94,12 +251,13 @@ fn show_graphemes<A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor>(
     
     egui_extras::TableBuilder::new(ui)
         .columns(Column::remainder(), 2)
+        .column(Column::auto())
         .vscroll(true)
         .body(|mut body| {
             for (id, quality) in inventory.phoneme_qualities() {
                 if let Some(phoneme) = phonemes.get_mut(id) {
                     body.row(row_height, |row| {
-                        show_grapheme_row(row, phoneme, quality);
+                        show_grapheme_row(row, id, phoneme, quality, editing);
                     });
                 }
             }
@@ -0,0 +1,134 @@
+use std::rc::Rc;
+
+use egui::RichText;
+use enum_iterator::all;
+use slotmap::{SlotMap, DefaultKey};
+
+use crate::app::FONT_ID;
+use crate::types::{SoundChangeContext, PhonemeQuality, PhonemeSelector, Feature, Features, Phoneme};
+use crate::types::category::Category;
+
+fn axis_editor<T: Category>(ui: &mut egui::Ui, axis: &mut Rc<[T]>) {
+    for variant in all::<T>() {
+        let mut checked = axis.contains(&variant);
+
+        if ui.checkbox(&mut checked, format!("{}", variant)).changed() {
+            let updated = all::<T>()
+                .filter(|other| if *other == variant { checked } else { axis.contains(other) })
+                .collect::<Vec<_>>();
+
+            *axis = Rc::from(updated);
+        }
+    }
+}
+
+/// Each feature may be required to be present, absent, or left unspecified
+fn features_editor(ui: &mut egui::Ui, features: &mut Features) {
+    egui::Grid::new("environment-features").show(ui, |ui| {
+        for feature in all::<Feature>() {
+            ui.label(format!("{}", feature));
+
+            let mut value = features.get(feature);
+
+            ui.selectable_value(&mut value, None, "Any");
+            ui.selectable_value(&mut value, Some(true), "+");
+            ui.selectable_value(&mut value, Some(false), "\u{2212}");
+
+            match value {
+                Some(value) => features.set(feature, value),
+                None => features.unset(feature)
+            }
+
+            ui.end_row();
+        }
+    });
+}
+
+fn selector_editor<A, B, C>(ui: &mut egui::Ui, selector: &mut PhonemeSelector<A, B, C>) 
+    where A: Category, B: Category, C: Category {
+
+    let PhonemeSelector { quality: PhonemeQuality(a, b, c), features } = selector;
+
+    ui.label("Leave a column empty to match any of its categories");
+
+    ui.horizontal_top(|ui| {
+        ui.vertical(|ui| axis_editor(ui, a));
+        ui.vertical(|ui| axis_editor(ui, b));
+        ui.vertical(|ui| axis_editor(ui, c));
+
+        ui.separator();
+
+        ui.vertical(|ui| features_editor(ui, features));
+    });
+}
+
+/// Returns true if the element should be removed from its environment
+fn element_editor(ui: &mut egui::Ui, element: &mut SoundChangeContext) -> bool {
+    ui.horizontal(|ui| {
+        let is_consonant = matches!(element, SoundChangeContext::Consonant(..));
+        if ui.selectable_label(is_consonant, "Consonant").clicked() && !is_consonant {
+            *element = SoundChangeContext::Consonant(PhonemeSelector::blank());
+        }
+
+        let is_vowel = matches!(element, SoundChangeContext::Vowel(..));
+        if ui.selectable_label(is_vowel, "Vowel").clicked() && !is_vowel {
+            *element = SoundChangeContext::Vowel(PhonemeSelector::blank());
+        }
+
+        let is_natural = matches!(element, SoundChangeContext::Natural(..));
+        if ui.selectable_label(is_natural, "Natural Class").clicked() && !is_natural {
+            *element = SoundChangeContext::Natural(Features::default());
+        }
+
+        let is_boundary = matches!(element, SoundChangeContext::Boundary);
+        if ui.selectable_label(is_boundary, "Word Boundary").clicked() {
+            *element = SoundChangeContext::Boundary;
+        }
+    });
+
+    match element {
+        SoundChangeContext::Consonant(selector) => selector_editor(ui, selector),
+        SoundChangeContext::Vowel(selector) => selector_editor(ui, selector),
+        SoundChangeContext::Natural(features) => {
+            ui.label("Matches any consonant or vowel with every chosen feature");
+
+            features_editor(ui, features);
+        },
+        _ => { /*  */ }
+    }
+
+    ui.separator();
+
+    let content = RichText::new("Remove").italics();
+
+    ui.button(content).clicked()
+}
+
+/// Edits one side of a sound change's environment as a sequence of segments
+pub fn environment_editor(ui: &mut egui::Ui, phonemes: &SlotMap<DefaultKey, Phoneme>, context: &mut SoundChangeContext) {
+    let mut sequence = context.to_sequence();
+
+    let mut removal = None;
+    for (idx, element) in sequence.iter_mut().enumerate() {
+        let content = RichText::new(element.write(phonemes))
+            .font(FONT_ID.to_owned());
+
+        ui.menu_button(content, |ui| {
+            if element_editor(ui, element) {
+                let _ = removal.insert(idx);
+
+                ui.close_menu();
+            }
+        });
+    }
+
+    if let Some(idx) = removal {
+        sequence.remove(idx);
+    }
+
+    if ui.button("+").clicked() {
+        sequence.push(SoundChangeContext::Consonant(PhonemeSelector::blank()));
+    }
+
+    *context = SoundChangeContext::from_sequence(sequence);
+}
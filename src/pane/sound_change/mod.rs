@@ -0,0 +1,881 @@
+pub mod environment;
+
+use std::fmt;
+use std::mem;
+
+use egui::RichText;
+use egui_extras::{Size, Column};
+use enum_iterator::{Sequence, all};
+use enum_map::EnumMap;
+use petgraph::stable_graph::NodeIndex;
+use slotmap::SlotMap;
+
+use crate::app::{FONT_ID, STATUS};
+
+use crate::pane;
+use crate::pane::language::LanguagePaneRole;
+
+use crate::state::Selection;
+use crate::types::{PhonemeQuality, SoundChange, SoundChangeContext, Change, Language, Phoneme, Prosody};
+use crate::types::{Application, Lexicon};
+use crate::types::{parse_prosodic_word, prosodic_word_to_string, parse_rules, print_rules, print_rule, print_sequence, Rule, RuleChange};
+use crate::types::{Stage, Period, Step, EMPTY};
+
+use crate::types::category::{Articulation, Region, Voicing};
+use crate::types::category::{Constriction, Place, Rounding};
+
+use crate::pane::util;
+
+use super::context::Context;
+
+#[derive(Clone, Copy, PartialEq, enum_map::Enum)]
+enum SoundChangeRequest {
+    Src,
+    Dst
+}
+
+impl SoundChangeRequest {
+    fn is_valid_source(&self, source: LanguagePaneRole) -> bool {
+        match self {
+            SoundChangeRequest::Src => matches!(source, LanguagePaneRole::Inventory),
+            SoundChangeRequest::Dst => true
+        }
+    }
+}
+
+/// What the sound change being built does to the phonemes picked for its source
+#[derive(Clone, Copy, PartialEq, Sequence)]
+enum Operation {
+    /// Replaces the source with the destination, either of which may be empty
+    Replace,
+    Metathesis,
+    Gemination,
+    Degemination
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Operation::Replace => "Replace",
+            Operation::Metathesis => "Metathesis",
+            Operation::Gemination => "Gemination",
+            Operation::Degemination => "Degemination"
+        })
+    }
+}
+
+pub struct SoundChangePane {
+    request: Option<SoundChangeRequest>,
+    current: EnumMap<SoundChangeRequest, Vec<Selection>>,
+    operation: Operation,
+    dialect: Option<NodeIndex<u32>>,
+    context: (SoundChangeContext, SoundChangeContext),
+    preview: String,
+    rules: Option<String>,
+    rule_errors: Vec<String>,
+    trace: bool,
+    /// The sound change being dragged to a new place in the chronology
+    dragging: Option<usize>
+}
+
+impl SoundChangePane {
+    pub fn new() -> Self {
+        Self {
+            request: None,
+            current: EnumMap::default(),
+            operation: Operation::Replace,
+            dialect: None,
+            context: (SoundChangeContext::Unrestricted, SoundChangeContext::Unrestricted),
+            preview: String::new(),
+            rules: None,
+            rule_errors: Vec::new(),
+            trace: false,
+            dragging: None
+        }
+    }
+
+    /// Shows the phonemes picked for one side of the sound change, in order
+    /// Each can be edited or removed from its context menu
+    fn sound_change_field(&mut self, ui: &mut egui::Ui, request: SoundChangeRequest, state: &mut crate::State) {
+        let mut toggle_state = if let Some(inner_request) = self.request {
+            inner_request == request
+        } else {
+            false
+        };
+
+        let content = match request {
+            SoundChangeRequest::Src => "From",
+            SoundChangeRequest::Dst => "To"
+        };
+
+        let content = RichText::from(content);
+
+        if ui.toggle_value(&mut toggle_state, content).clicked() {
+            if toggle_state { 
+                let _ = self.request.insert(request);
+            } else { 
+                let _ = self.request.take();
+            };
+
+            state.buffer_state = toggle_state;
+        }
+
+        let mut removal = None;
+        for (idx, selection) in self.current[request].iter_mut().enumerate() {
+            // Sources are inventory phonemes, which may have been edited since they were picked
+            if let SoundChangeRequest::Src = request {
+                if let Some(phoneme) = state.phonemes.get(selection.phoneme.id()) {
+                    selection.phoneme = phoneme.clone();
+                }
+            }
+
+            egui_extras::StripBuilder::new(ui)
+                .size(Size::exact(FONT_ID.size * 2.))
+                .horizontal(|mut strip| { strip.cell(|ui| {
+                    let Selection { phoneme, quality, .. } = selection;
+
+                    let bg_color = if phoneme.phone.is_consonant() {
+                        let quality: PhonemeQuality<Articulation, Region, Voicing> = PhonemeQuality::from_raw(quality.clone());
+
+                        util::cell_color(ui, Some(quality))
+                    } else if phoneme.phone.is_vowel() {
+                        let quality: PhonemeQuality<Constriction, Place, Rounding> = PhonemeQuality::from_raw(quality.clone());
+
+                        util::cell_color(ui, Some(quality))
+                    } else {
+                        util::cell_color(ui, phoneme.phone.glide().map(|[start, _]| start))
+                    };
+        
+                    let content = format!("{}", phoneme);
+                    let content = RichText::new(content)
+                        .font(FONT_ID.to_owned())
+                        .background_color(egui::Color32::TRANSPARENT);
+
+                    let mut rect = ui.available_rect_before_wrap();
+                    
+                    *(rect.bottom_mut()) += ui.style().spacing.item_spacing.y;
+        
+                    ui.painter().rect_filled(rect, 0., bg_color);
+                    ui.vertical_centered(|ui| {
+                        let content = egui::Label::new(content)
+                            .wrap(false)
+                            .sense(egui::Sense::click());
+
+                        ui.add(content).context_menu(|ui| {
+                            // Destinations are free to be modified before they're added
+                            // Diphthongs aren't charted in a cell, so there's nothing to modify
+                            if let (SoundChangeRequest::Dst, false) = (request, phoneme.phone.is_diphthong()) {
+                                if phoneme.phone.is_consonant() {
+                                    let quality: PhonemeQuality<Articulation, Region, Voicing> = //
+                                        PhonemeQuality::from_raw(quality.clone());
+
+                                    let context = Context::Free { quality, phoneme };
+            
+                                    pane::context::cell_context(ui, &state.ipa, &mut state.phonemes, context);
+                                } else if phoneme.phone.is_vowel() {
+                                    let quality: PhonemeQuality<Constriction, Place, Rounding> = //
+                                        PhonemeQuality::from_raw(quality.clone());
+                                        
+                                    let context = Context::Free { quality, phoneme };
+        
+                                    pane::context::cell_context(ui, &state.ipa, &mut state.phonemes, context);
+                                }
+
+                                ui.separator();
+                            }
+
+                            if ui.button("Remove").clicked() {
+                                let _ = removal.insert(idx);
+
+                                ui.close_menu();
+                            }
+                        });
+                    });
+                })});
+        }
+
+        if let Some(idx) = removal {
+            self.current[request].remove(idx);
+        }
+
+        if self.current[request].is_empty() {
+            ui.label(RichText::new(EMPTY).font(FONT_ID.to_owned()));
+        }
+    }
+
+    /// The rule described by the phonemes that were picked, if they make one
+    fn rule(&mut self) -> Option<Rule> {
+        let src = self.current[SoundChangeRequest::Src]
+            .iter()
+            .map(|selection| selection.phoneme.id())
+            .collect::<Vec<_>>();
+
+        let mut dst = self.current[SoundChangeRequest::Dst]
+            .iter()
+            .map(|Selection { phoneme, quality, .. }| (phoneme.clone(), quality.clone()))
+            .collect::<Vec<_>>();
+
+        let change = match (self.operation, &src[..]) {
+            (Operation::Replace, [src]) if dst.len() == 1 => {
+                let (phoneme, quality) = dst.pop().unwrap();
+
+                RuleChange::Substitution { src: *src, dst: phoneme, quality }
+            },
+            (Operation::Replace, src) if !src.is_empty() || !dst.is_empty() => {
+                RuleChange::Sequence { src: src.to_vec(), dst }
+            },
+            (Operation::Metathesis, src) if src.len() > 1 => RuleChange::Metathesis { src: src.to_vec() },
+            (Operation::Gemination, [src]) => RuleChange::Gemination { src: *src },
+            (Operation::Degemination, [src]) => RuleChange::Degemination { src: *src },
+            _ => return None
+        };
+
+        for (_, selections) in self.current.iter_mut() {
+            selections.clear();
+        }
+
+        Some(Rule {
+            change,
+            context: mem::replace(&mut self.context, (
+                SoundChangeContext::Unrestricted, 
+                SoundChangeContext::Unrestricted
+            )),
+            prosody: Default::default(),
+            application: Default::default()
+        })
+    }
+}
+
+/// Edits how sporadically a sound change applies
+fn application_editor(ui: &mut egui::Ui, application: &mut Application) {
+    let mut sporadic = application.probability.is_some();
+    if ui.checkbox(&mut sporadic, "Sporadic").changed() {
+        application.probability = sporadic.then_some(0.5);
+    }
+
+    if let Some(probability) = &mut application.probability {
+        let mut percent = *probability * 100.;
+
+        let probability_editor = egui::Slider::new(&mut percent, 0.0..=100.0)
+            .suffix("%")
+            .text("Probability");
+
+        if ui.add(probability_editor).changed() {
+            *probability = percent / 100.;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Seed");
+            ui.add(egui::DragValue::new(&mut application.seed));
+
+            if ui.button("New Seed").clicked() {
+                application.seed = ui.input(|input| input.time.to_bits());
+            }
+        });
+
+        ui.checkbox(&mut application.diffusion, "Lexical Diffusion")
+            .on_hover_text("Rolls once per word, so that the change reaches either all of a word or none of it");
+    }
+}
+
+/// Picks the entries of the root language's lexicon that a sound change skips
+fn exceptions_editor(
+    ui: &mut egui::Ui, 
+    phonemes: &SlotMap<slotmap::DefaultKey, Phoneme>, 
+    lexicon: &Lexicon, 
+    exceptions: &mut Vec<crate::types::EntryKey>) {
+
+    if lexicon.is_empty() {
+        ui.label("The root language's lexicon is empty");
+
+        return;
+    }
+
+    let mut entries = lexicon.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|(_, entry)| entry.gloss.to_lowercase());
+
+    egui::ScrollArea::vertical()
+        .max_height(FONT_ID.size * 16.)
+        .show(ui, |ui| {
+
+        for (key, entry) in entries {
+            let mut checked = exceptions.contains(&key);
+
+            let content = format!("{} /{}/", entry.gloss, prosodic_word_to_string(phonemes, &entry.form, &entry.prosody));
+
+            if ui.checkbox(&mut checked, content).changed() {
+                if checked {
+                    exceptions.push(key);
+                } else {
+                    exceptions.retain(|exception| *exception != key);
+                }
+            }
+        }
+    });
+}
+
+fn show_sound_change(
+    mut row: egui_extras::TableRow<'_, '_>, 
+    phonemes: &SlotMap<slotmap::DefaultKey, Phoneme>,
+    parent: &Language, 
+    child: &Language, 
+    sound_change: &SoundChange,
+    lexicon: &Lexicon,
+    application: &mut Application) -> egui::Response {
+
+    match &sound_change.change {
+        Change::Substitution { src, dst } => {
+            row.col(|ui| {
+                let cell_color = match phonemes.get(*src).map(|phoneme| &phoneme.phone) {
+                    Some(phone) if phone.is_consonant() => util::cell_color(ui, parent.consonants.get_quality(*src)),
+                    Some(phone) if phone.is_vowel() => util::cell_color(ui, parent.vowels.get_quality(*src)),
+                    Some(phone) => util::cell_color(ui, phone.glide().map(|[start, _]| start)),
+                    // Removed phonemes are left uncolored
+                    None => ui.visuals().window_fill
+                };
+        
+                ui.painter().rect_filled(
+                    { 
+                        let mut rect = ui.available_rect_before_wrap();
+
+                        rect.set_bottom(rect.bottom() - ui.style().spacing.item_spacing.y);
+                        rect
+                    },
+                    0., cell_color);
+
+                let content = RichText::new(print_sequence(phonemes, &[*src]))
+                    .font(FONT_ID.to_owned())
+                    .background_color(egui::Color32::TRANSPARENT);
+
+                ui.label(content); 
+            });
+
+            row.col(|ui| {
+                let cell_color = match phonemes.get(*dst).map(|phoneme| &phoneme.phone) {
+                    Some(phone) if phone.is_consonant() => util::cell_color(ui, child.consonants.get_quality(*dst)),
+                    Some(phone) if phone.is_vowel() => util::cell_color(ui, child.vowels.get_quality(*dst)),
+                    Some(phone) => util::cell_color(ui, phone.glide().map(|[start, _]| start)),
+                    // Removed phonemes are left uncolored
+                    None => ui.visuals().window_fill
+                };
+        
+                ui.painter().rect_filled(
+                    { 
+                        let mut rect = ui.available_rect_before_wrap();
+
+                        rect.set_bottom(rect.bottom() - ui.style().spacing.item_spacing.y);
+                        rect
+                    },
+                    0., cell_color);
+
+                let content = RichText::new(print_sequence(phonemes, &[*dst]))
+                    .font(FONT_ID.to_owned())
+                    .background_color(egui::Color32::TRANSPARENT);
+
+                ui.label(content); 
+            });
+        },
+        Change::Sequence { src, dst } => {
+            row.col(|ui| { ui.label(RichText::new(print_sequence(phonemes, src)).font(FONT_ID.to_owned())); });
+            row.col(|ui| { ui.label(RichText::new(print_sequence(phonemes, dst)).font(FONT_ID.to_owned())); });
+        },
+        Change::Metathesis { src } => {
+            row.col(|ui| { ui.label(RichText::new(print_sequence(phonemes, src)).font(FONT_ID.to_owned())); });
+            row.col(|ui| { ui.label("Metathesis"); });
+        },
+        Change::Gemination { src } => {
+            row.col(|ui| { ui.label(RichText::new(print_sequence(phonemes, &[*src])).font(FONT_ID.to_owned())); });
+            row.col(|ui| { ui.label("Gemination"); });
+        },
+        Change::Degemination { src } => {
+            row.col(|ui| { ui.label(RichText::new(print_sequence(phonemes, &[*src, *src])).font(FONT_ID.to_owned())); });
+            row.col(|ui| { ui.label("Degemination"); });
+        },
+        Change::Shift { src, dst, products } => {
+            row.col(|ui| {
+                ui.label(egui::RichText::new(src.write(phonemes)).font(FONT_ID.to_owned()));
+            });
+
+            row.col(|ui| {
+                let products = products
+                    .iter()
+                    .map(|(_, product)| print_sequence(phonemes, &[*product]))
+                    .collect::<Vec<_>>();
+
+                let content = egui::RichText::new(format!("{} {}", dst.write(phonemes), products.join(" ")))
+                    .font(FONT_ID.to_owned());
+
+                ui.label(content);
+            });
+        }
+    }
+
+    row.col(|ui| {
+        let content = egui::RichText::new(sound_change.environment(phonemes))
+            .font(FONT_ID.to_owned());
+
+        ui.label(content);
+    });
+
+    row.col(|ui| {
+        ui.menu_button(format!("{}", application), |ui| application_editor(ui, application));
+    });
+
+    row.col(|ui| {
+        let content = match application.exceptions.len() {
+            0 => String::from("No Exceptions"),
+            1 => String::from("1 Exception"),
+            count => format!("{} Exceptions", count)
+        };
+
+        ui.menu_button(content, |ui| exceptions_editor(ui, phonemes, lexicon, &mut application.exceptions));
+    });
+
+    let mut response = None;
+    row.col(|ui| {
+        response = Some(ui.button("Delete"));
+    });
+
+    response.unwrap()
+}
+
+/// Lists the word as it was after each sound change, marking the ones that fired
+fn show_trace(
+    ui: &mut egui::Ui, 
+    state: &crate::State, 
+    id: NodeIndex<u32>, 
+    word: &[slotmap::DefaultKey], 
+    prosody: &Prosody) {
+
+    let dialect = &state.dialects[state.language_tree[id]];
+    let steps = state.trace(word, prosody, id, None);
+
+    let form = |word: &[slotmap::DefaultKey], prosody: &Prosody| {
+        RichText::new(prosodic_word_to_string(&state.phonemes, word, prosody)).font(FONT_ID.to_owned())
+    };
+
+    egui::ScrollArea::vertical()
+        .max_height(FONT_ID.size * 12.)
+        .show(ui, |ui| {
+
+        egui::Grid::new("sound-change-trace").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("");
+            ui.label(form(word, prosody));
+            ui.end_row();
+
+            for period in dialect.chronology() {
+                match period {
+                    Period::Stage(stage) => {
+                        ui.label("");
+                        ui.strong(dialect.stages[stage].name.as_str());
+                    },
+                    Period::SoundChange(idx) => {
+                        let Step { word, prosody, fired } = &steps[idx];
+
+                        let rule = RichText::new(print_rule(&state.phonemes, &dialect.sound_changes[idx]))
+                            .font(FONT_ID.to_owned());
+
+                        if *fired {
+                            ui.label("✔");
+                            ui.label(rule.strong());
+                            ui.label(form(word, prosody).strong());
+                        } else {
+                            ui.label("");
+                            ui.label(rule.weak());
+                            ui.label(form(word, prosody).weak());
+                        }
+                    }
+                }
+
+                ui.end_row();
+            }
+        });
+    });
+}
+
+impl pane::Pane for SoundChangePane {
+    fn title(&self, _state: &crate::State) -> std::rc::Rc<str> {
+        std::rc::Rc::from("Sound Changes")
+    }
+
+    fn setup<'a, 'b: 'a>(&'a mut self, state: &crate::State, _ctx: &egui::Context) -> egui::Window<'b> {
+        egui::Window::new(self.title(state).as_ref())
+    }
+
+    fn show(&mut self, _windowed: bool, state: &mut crate::State, ui: &mut egui::Ui) {
+        // The target dialect may have been deleted since it was selected
+        if self.dialect.map_or(false, |id| state.parent(id) != Some(state.inventory_index)) {
+            let _ = self.dialect.take();
+            let _ = self.rules.take();
+        }
+
+        // Collect from the buffer
+        if let Some(request) = self.request {
+            if let Some(buffer_contents) = state.buffer.take() {
+                let Selection { ref source, .. } = buffer_contents;
+
+                if request.is_valid_source(*source) {
+                    self.current[request].push(buffer_contents);
+
+                    // TODO: ESC should cancel selection
+                    // Further phonemes are appended by picking the field again
+                    self.request = match (request, self.operation) {
+                        (SoundChangeRequest::Src, Operation::Replace) => Some(SoundChangeRequest::Dst),
+                        _ => None
+                    };
+                } else {
+                    // TODO: This else statement assumes that there will never
+                    // be another variant of SoundChangeRequest added
+                    // I never intend to, but it should be noted.
+                    let mut status = STATUS.lock();
+                    status.clear();
+                    status.push_str("Sound change's source must be selected from the inventory. Select another phoneme.");
+                }
+            }
+        }
+
+        let panel_height = ui.style().spacing.item_spacing.y * 2.;
+        let panel_height = panel_height + FONT_ID.size;
+
+        egui::TopBottomPanel::top(util::new_id())
+            .exact_height(panel_height)
+            .show_inside(ui, |ui| {
+
+            ui.horizontal(|ui| {
+                let mut dialects = state.language_tree
+                    .neighbors_directed(state.inventory_index, petgraph::Outgoing)
+                    .peekable();
+                
+                let content = match dialects.peek() {
+                    Some(..) => "Select target dialect",
+                    None => "Selected language must have at least one dialect to apply sound changes",
+                };
+
+                ui.label(content);
+
+                for child in dialects {
+                    let content = state.dialects[state.language_tree[child]].name.clone();
+
+                    if ui.selectable_value(&mut self.dialect, Some(child), content.as_ref()).clicked() {
+                        let _ = self.rules.take();
+                    }
+                }
+
+                if let Some(id) = self.dialect {
+                    ui.separator();
+
+                    let mut text_state = self.rules.is_some();
+                    if ui.toggle_value(&mut text_state, "Edit as Text").clicked() {
+                        self.rules = if text_state {
+                            let dialect = &state.dialects[state.language_tree[id]];
+
+                            Some(print_rules(&state.phonemes, dialect))
+                        } else {
+                            None
+                        };
+
+                        self.rule_errors.clear();
+                    }
+
+                    if ui.add_enabled(self.rules.is_none(), egui::Button::new("Add Stage")).clicked() {
+                        let dialect = &mut state.dialects[state.language_tree[id]];
+
+                        let stage = Stage { 
+                            name: format!("Stage {}", dialect.stages.len() + 1), 
+                            start: dialect.sound_changes.len() 
+                        };
+
+                        dialect.stages.push(stage);
+                    }
+                }
+            });
+
+            let mut margin = egui::Margin::default();
+
+            margin.bottom += ui.style().spacing.item_spacing.y * 2.;
+            egui::Frame::none().outer_margin(margin).show(ui, |ui| {
+                ui.horizontal_top(|ui| {
+                    egui::ComboBox::from_id_source("sound-change-operation")
+                        .selected_text(format!("{}", self.operation))
+                        .show_ui(ui, |ui| {
+                            for operation in all::<Operation>() {
+                                ui.selectable_value(&mut self.operation, operation, format!("{}", operation));
+                            }
+                        });
+
+                    self.sound_change_field(ui, SoundChangeRequest::Src, state);
+
+                    if self.operation == Operation::Replace {
+                        self.sound_change_field(ui, SoundChangeRequest::Dst, state);
+                    }
+
+                    ui.label("/");
+
+                    environment::environment_editor(ui, &state.phonemes, &mut self.context.0);
+
+                    ui.label("_");
+
+                    environment::environment_editor(ui, &state.phonemes, &mut self.context.1);
+
+                    ui.separator();
+                    
+                    match self.dialect {
+                        Some(dialect) if ui.button("Add").clicked() => {
+                            if let Some(rule) = self.rule() {
+                                state.add_sound_change(dialect, rule);
+                            }
+                        },
+                        _ => { /*  */ },
+                    }
+                });
+            });
+        });
+
+        if let Some(id) = self.dialect {
+            egui::TopBottomPanel::bottom(util::new_id())
+                .show_inside(ui, |ui| {
+
+                let parsed = ui.horizontal(|ui| {
+                    ui.label("Test word");
+
+                    let preview_editor = egui::TextEdit::singleline(&mut self.preview)
+                        .font(FONT_ID.to_owned());
+
+                    ui.add(preview_editor);
+
+                    ui.toggle_value(&mut self.trace, "Trace");
+
+                    let parent = state.lineage(state.inventory_index);
+
+                    match parse_prosodic_word(&self.preview, &parent) {
+                        Some((word, prosody)) if !word.is_empty() => {
+                            let (reflex, reflex_prosody) = state.derive(&word, &prosody, id, None);
+
+                            let content = format!("→ {}", prosodic_word_to_string(&state.phonemes, &reflex, &reflex_prosody));
+                            let content = RichText::new(content)
+                                .font(FONT_ID.to_owned());
+
+                            ui.label(content);
+
+                            Some((word, prosody))
+                        },
+                        Some(..) => None,
+                        None => {
+                            ui.label("Word contains phonemes outside of the parent language");
+
+                            None
+                        }
+                    }
+                }).inner;
+
+                if let (true, Some((word, prosody))) = (self.trace, parsed) {
+                    show_trace(ui, state, id, &word, &prosody);
+                }
+            });
+
+            if let Some(rules) = &mut self.rules {
+                ui.horizontal(|ui| {
+                    if ui.button("Apply").clicked() {
+                        let parsed = {
+                            let source = state.lineage(state.inventory_index);
+                            let target = state.lineage(id);
+
+                            parse_rules(rules, &source, &target)
+                        };
+
+                        match parsed {
+                            Ok((parsed, stages)) => {
+                                state.replace_sound_changes(id, parsed, stages);
+
+                                *rules = print_rules(&state.phonemes, &state.dialects[state.language_tree[id]]);
+
+                                self.rule_errors.clear();
+                            },
+                            Err(errors) => {
+                                self.rule_errors = errors
+                                    .into_iter()
+                                    .map(|(line, error)| format!("Line {}: {}", line, error))
+                                    .collect();
+                            }
+                        }
+                    }
+
+                    ui.label("One rule per line, such as 'p > f / V_V', 'h > ∅', 'sk > Metathesis' or '[Plosive Voiceless] > [Voiced] / V_V', stages such as 'Old:', and sporadic rules such as 's > h ? 50%'");
+                });
+
+                for error in self.rule_errors.iter() {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    let rule_editor = egui::TextEdit::multiline(rules)
+                        .font(FONT_ID.to_owned())
+                        .desired_width(f32::INFINITY);
+
+                    ui.add(rule_editor);
+                });
+
+                return;
+            }
+
+            let dialect = &state.dialects[state.language_tree[id]];
+
+            let row_height = FONT_ID.size;
+            let row_height = row_height + ui.style().spacing.item_spacing.y * 4.;
+            let row_height = row_height + ui.style().spacing.button_padding.y * 2.;
+
+            let chronology = dialect.chronology();
+
+            // Edited apart from the dialect, which the rows borrow
+            let mut names = dialect.stages
+                .iter()
+                .map(|stage| stage.name.clone())
+                .collect::<Vec<_>>();
+
+            let lexicon = &state.dialects[state.language_tree[state.root]].lexicon;
+
+            let mut deletion_queue = Vec::new();
+            let mut stage_removal = None;
+            let mut applications = Vec::new();
+
+            let mut dragging = self.dragging;
+            let mut drop_target = None;
+
+            let pointer = ui.input(|i| i.pointer.interact_pos());
+
+            ui.vertical_centered(|ui| {
+                let width = ui.available_rect_before_wrap().x_range();
+
+                egui_extras::TableBuilder::new(ui)
+                    .column(Column::auto())
+                    .columns(Column::remainder(), 6)
+                    .vscroll(true)
+                    .body(|body| {
+
+                    body.rows(row_height, chronology.len(), 
+                        |position, mut row| {
+                            let (rect, _) = match chronology[position] {
+                                Period::Stage(stage) => {
+                                    let handle = row.col(|_| { /*  */ });
+
+                                    row.col(|ui| {
+                                        let name_editor = egui::TextEdit::singleline(&mut names[stage])
+                                            .font(egui::TextStyle::Heading);
+
+                                        ui.add(name_editor);
+                                    });
+
+                                    for _ in 0..4 {
+                                        row.col(|_| { /*  */ });
+                                    }
+
+                                    row.col(|ui| {
+                                        if ui.button("Remove Stage").clicked() {
+                                            let _ = stage_removal.insert(stage);
+                                        }
+                                    });
+
+                                    handle
+                                },
+                                Period::SoundChange(idx) => {
+                                    let handle = row.col(|ui| {
+                                        let handle = ui.add(egui::Label::new("☰").sense(egui::Sense::drag()));
+
+                                        if handle.hovered() {
+                                            ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+                                        }
+
+                                        if handle.drag_started() {
+                                            let _ = dragging.insert(idx);
+                                        }
+                                    });
+
+                                    let parent = &state.dialects[state.inventory];
+                                    let sound_change = &dialect.sound_changes[idx];
+
+                                    let mut application = sound_change.application.clone();
+
+                                    let response = show_sound_change(
+                                        row, 
+                                        &state.phonemes, 
+                                        parent, 
+                                        dialect, 
+                                        sound_change, 
+                                        lexicon, 
+                                        &mut application
+                                    );
+
+                                    if application != sound_change.application {
+                                        applications.push((idx, application));
+                                    }
+
+                                    if response.clicked() {
+                                        deletion_queue.insert(0, idx);
+                                    }
+
+                                    handle
+                                }
+                            };
+
+                            // Sound changes dropped onto a stage become the first of that stage
+                            match (dragging, pointer) {
+                                (Some(..), Some(pointer)) if rect.top() <= pointer.y && pointer.y < rect.bottom() => {
+                                    let after = matches!(chronology[position], Period::Stage(..)) //
+                                        || pointer.y > rect.center().y;
+
+                                    let line = if after { rect.bottom() } else { rect.top() };
+
+                                    let _ = drop_target.insert((if after { position + 1 } else { position }, line));
+                                },
+                                _ => { /*  */ }
+                            }
+                        }
+                    );
+                });
+
+                if let Some((_, line)) = drop_target {
+                    ui.painter().hline(width, line, ui.visuals().selection.stroke);
+                }
+            });
+
+            if dragging.is_some() {
+                ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+            }
+
+            let dialect = &mut state.dialects[state.language_tree[id]];
+
+            for (stage, name) in dialect.stages.iter_mut().zip(names) {
+                stage.name = name;
+            }
+
+            for (idx, application) in applications {
+                dialect.sound_changes[idx].application = application;
+            }
+
+            if let Some(stage) = stage_removal {
+                dialect.stages.remove(stage);
+            }
+
+            if ui.input(|i| i.pointer.any_released()) {
+                if let (Some(idx), Some((position, _))) = (dragging.take(), drop_target) {
+                    dialect.move_sound_change(idx, position);
+                }
+            }
+
+            self.dragging = dragging;
+
+            for idx in deletion_queue.drain(0..) {
+                state.remove_sound_change(id, idx);
+            }
+        }
+    }
+
+    fn on_dialect_change(&mut self, _state: &mut crate::State) {
+        self.dialect = None;
+        self.dragging = None;
+        self.preview.clear();
+        self.rules = None;
+        self.rule_errors.clear();
+    }
+}
\ No newline at end of file
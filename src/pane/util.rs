@@ -2,7 +2,35 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::app::FONT_ID;
 use crate::types::{PhonemeQuality, Phoneme};
-use crate::types::category::{Outer, Inner, Pair, CategoryColor};
+use crate::types::category::{Outer, Inner, Pair, Voicing, Rounding};
+
+pub trait CategoryColor {
+    fn as_color(&self) -> egui::Color32;
+}
+
+impl CategoryColor for Voicing {
+    fn as_color(&self) -> egui::Color32 {
+        match self {
+            Voicing::Voiceless => egui::Color32::GOLD,
+            Voicing::Voiced => egui::Color32::RED
+        }
+    }
+}
+
+impl CategoryColor for Rounding {
+    fn as_color(&self) -> egui::Color32 {
+        match self {
+            Rounding::Unrounded => egui::Color32::LIGHT_BLUE,
+            Rounding::Rounded => egui::Color32::BLUE
+        }
+    }
+}
+
+impl From<Phoneme> for egui::RichText {
+    fn from(phoneme: Phoneme) -> Self {
+        egui::RichText::from(format!("{}", phoneme)).font(FONT_ID.to_owned())
+    }
+}
 
 pub fn cell_color<A: Outer<B, C>, B: Inner<C>, C: Pair + CategoryColor>(
     ui: &egui::Ui,
@@ -0,0 +1,445 @@
+use std::fmt;
+
+use crate::State;
+use crate::types::Diacritic;
+
+/// Incremented whenever a change to `State` alters its serialized form
+/// Every increment must be accompanied by a step in `MIGRATIONS`
+pub const FORMAT_VERSION: u32 = 3;
+
+/// Each step rewrites a project saved at the version matching its index into the next version
+/// Steps work on the text of the file, because older layouts can't be read into the current types
+/// A step fails with a description of what it couldn't read, rather than passing it through unchanged
+/// Sessions saved before projects were versioned are treated as version 0
+const MIGRATIONS: [fn(String) -> Result<String, String>; FORMAT_VERSION as usize] = [
+    // 0 -> 1: The unversioned session held `State` under the same field as a project does
+    Ok,
+    // 1 -> 2: Diacritics were appended to a phoneme's symbol, rather than kept alongside it
+    split_diacritics,
+    // 2 -> 3: Sound changes only ever replaced one phoneme with another
+    wrap_substitutions
+];
+
+/// Finds the first `field` of `text` that isn't part of a string or character literal,
+/// so that names, glosses and notes which happen to contain it are left alone
+/// `text` must begin outside of any literal
+fn find_field(text: &str, field: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (idx, c) in text.char_indices() {
+        match quote {
+            Some(..) if escaped => escaped = false,
+            Some(..) if c == '\\' => escaped = true,
+            Some(end) if c == end => quote = None,
+            Some(..) => { /*  */ },
+            None if text[idx..].starts_with(field) => return Some(idx),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None => { /*  */ }
+        }
+    }
+
+    None
+}
+
+/// Moves the marks out of every phoneme's symbol into its set of diacritics
+/// Marks that were drawn differently before (such as the ring above) are read as their current diacritic
+fn split_diacritics(content: String) -> Result<String, String> {
+    const FIELD: &str = "symbol: \"";
+
+    let mut migrated = String::with_capacity(content.len());
+
+    let mut remaining = content.as_str();
+    while let Some(start) = find_field(remaining, FIELD) {
+        let (before, rest) = remaining.split_at(start + FIELD.len() - 1);
+        migrated.push_str(before);
+
+        let mut escaped = false;
+        let end = rest
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| {
+                let end = !escaped && *c == '"';
+                escaped = !escaped && *c == '\\';
+                end
+            })
+            .map(|(idx, _)| idx + 1)
+            .ok_or_else(|| String::from("A symbol is missing its closing quote"))?;
+
+        let (literal, rest) = rest.split_at(end);
+        remaining = rest;
+
+        let symbol = ron::from_str::<String>(literal)
+            .map_err(|error| format!("Unable to read the symbol {}: {}", literal, error))?;
+
+        let mut diacritics = Vec::new();
+        let base = symbol
+            .chars()
+            .enumerate()
+            .filter(|(idx, c)| match Diacritic::from_mark(*c) {
+                Some(diacritic) if *idx > 0 => {
+                    diacritics.push(format!("{:?}", diacritic));
+                    false
+                },
+                _ => true
+            })
+            .map(|(_, c)| c)
+            .collect::<String>();
+
+        if diacritics.is_empty() {
+            migrated.push_str(literal);
+        } else {
+            let base = ron::to_string(&base)
+                .map_err(|error| format!("Unable to write the symbol {}: {}", literal, error))?;
+
+            migrated.push_str(&base);
+            migrated.push_str(format!(", diacritics: [{}]", diacritics.join(", ")).as_str());
+        }
+    }
+
+    migrated.push_str(remaining);
+    Ok(migrated)
+}
+
+/// Wraps the phonemes that each sound change maps between in a `Change::Substitution`
+fn wrap_substitutions(content: String) -> Result<String, String> {
+    const FIELDS: [&str; 2] = ["src:", "dst:"];
+
+    /// Splits a key, such as `(idx: 1, version: 1)`, off of the start of `text`
+    fn split_key(text: &str) -> Option<(&str, &str)> {
+        let text = text.trim_start();
+
+        if !text.starts_with('(') { return None; }
+
+        let mut depth = 0;
+        for (idx, c) in text.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' if depth == 1 => return Some(text.split_at(idx + 1)),
+                ')' => depth -= 1,
+                _ => { /*  */ }
+            }
+        }
+
+        None
+    }
+
+    /// Reads the key of `field` from the start of `text`, along with whatever follows it
+    fn split_field<'a>(text: &'a str, field: &str) -> Result<(&'a str, &'a str), String> {
+        text.trim_start()
+            .strip_prefix(field)
+            .and_then(split_key)
+            .ok_or_else(|| format!("Expected a phoneme key after '{}' in a sound change", field))
+    }
+
+    let mut migrated = String::with_capacity(content.len());
+
+    let mut remaining = content.as_str();
+    while let Some((start, first)) = FIELDS
+        .iter()
+        .filter_map(|field| find_field(remaining, field).map(|start| (start, *field)))
+        .min() {
+
+        let (before, rest) = remaining.split_at(start);
+        migrated.push_str(before);
+
+        // Fields are saved in the order they're declared, but nothing else relies on it
+        let second = if first == FIELDS[0] { FIELDS[1] } else { FIELDS[0] };
+
+        let (key, rest) = split_field(rest, first)?;
+
+        let rest = rest
+            .trim_start()
+            .strip_prefix(',')
+            .ok_or_else(|| format!("Expected '{}' to follow '{}' in a sound change", second, first))?;
+
+        let (other, rest) = split_field(rest, second)?;
+
+        let (src, dst) = if first == FIELDS[0] { (key, other) } else { (other, key) };
+
+        migrated.push_str(format!("change: Substitution(src: {}, dst: {})", src, dst).as_str());
+        remaining = rest;
+    }
+
+    migrated.push_str(remaining);
+    Ok(migrated)
+}
+
+pub const EXTENSION: &str = "phon";
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Io(std::io::Error),
+    Format(ron::Error),
+    Syntax(ron::error::SpannedError),
+    Version(u32),
+    /// The version the project couldn't be migrated from, and what went wrong
+    Migration(u32, String)
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::Io(error) => write!(f, "{}", error),
+            ProjectError::Format(error) => write!(f, "Unable to write project: {}", error),
+            ProjectError::Syntax(error) => write!(f, "Unable to read project: {}", error),
+            ProjectError::Version(version) =>
+                write!(f, "Project was saved by a newer version of the format ({} > {})", version, FORMAT_VERSION),
+            ProjectError::Migration(version, error) => 
+                write!(f, "Unable to migrate project from version {}: {}", version, error)
+        }
+    }
+}
+
+impl std::error::Error for ProjectError { /*  */ }
+
+#[derive(serde::Serialize)]
+struct ProjectRef<'a> {
+    version: u32,
+    state: &'a State
+}
+
+/// Read on its own so that the version can be checked before the rest of the file
+#[derive(serde::Deserialize)]
+struct Header {
+    #[serde(default)]
+    version: u32
+}
+
+#[derive(serde::Deserialize)]
+struct Project {
+    state: State
+}
+
+pub fn to_string(state: &State) -> Result<String, ProjectError> {
+    let project = ProjectRef { version: FORMAT_VERSION, state };
+
+    ron::ser::to_string_pretty(&project, ron::ser::PrettyConfig::default())
+        .map_err(ProjectError::Format)
+}
+
+pub fn from_str(content: &str) -> Result<State, ProjectError> {
+    let Header { version } = ron::from_str(content)
+        .map_err(ProjectError::Syntax)?;
+
+    if version > FORMAT_VERSION {
+        return Err(ProjectError::Version(version));
+    }
+
+    let content = MIGRATIONS
+        .iter()
+        .enumerate()
+        .skip(version as usize)
+        .try_fold(String::from(content), |content, (version, step)| step(content)
+            .map_err(|error| ProjectError::Migration(version as u32, error)))?;
+
+    let Project { mut state } = ron::from_str(&content)
+        .map_err(ProjectError::Syntax)?;
+
+    state.remove_orphans();
+    state.merge_ipa();
+
+    Ok(state)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(path: &std::path::Path, state: &State) -> Result<(), ProjectError> {
+    std::fs::write(path, to_string(state)?).map_err(ProjectError::Io)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(path: &std::path::Path) -> Result<State, ProjectError> {
+    from_str(&std::fs::read_to_string(path).map_err(ProjectError::Io)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    use crate::types::{Entry, Language, PhonemeQuality, parse_rules, print_rules};
+    use crate::types::category::Region;
+
+    #[test]
+    fn fields_in_literals_are_skipped() {
+        assert_eq!(find_field("gloss: \"src: x\", src: (", "src:"), Some(17));
+        assert_eq!(find_field("notes: \"an \\\"src:\\\" \", src:", "src:"), Some(23));
+        assert_eq!(find_field("name: 'x', symbol: \"", "symbol: \""), Some(11));
+        assert_eq!(find_field("gloss: \"symbol: \\\"\"", "symbol: \""), None);
+    }
+
+    #[test]
+    fn marks_are_split_off_of_symbols() {
+        let migrated = split_diacritics(String::from("(symbol: \"t̪ʰ\", grapheme: \"symbol: \\\"d̪\\\"\")")).unwrap();
+
+        assert_eq!(migrated, "(symbol: \"tʰ\", diacritics: [Dental], grapheme: \"symbol: \\\"d̪\\\"\")");
+
+        // A mark on its own has nothing to modify, so it stays the symbol
+        let unmarked = String::from("(symbol: \"a\"), (symbol: \"\u{0303}\")");
+        assert_eq!(split_diacritics(unmarked.clone()).unwrap(), unmarked);
+
+        assert!(split_diacritics(String::from("(symbol: \"t̪")).is_err());
+    }
+
+    #[test]
+    fn substitutions_are_wrapped_whichever_field_comes_first() {
+        let wrapped = "(change: Substitution(src: (idx: 1, version: 1), dst: (idx: 2, version: 1)), context: ())";
+
+        let migrated = wrap_substitutions(String::from("(src: (idx: 1, version: 1), dst: (idx: 2, version: 1), context: ())"));
+        assert_eq!(migrated.unwrap(), wrapped);
+
+        let migrated = wrap_substitutions(String::from("(dst: (idx: 2, version: 1),\n src: (idx: 1, version: 1), context: ())"));
+        assert_eq!(migrated.unwrap(), wrapped);
+    }
+
+    #[test]
+    fn substitutions_missing_a_field_are_reported() {
+        assert!(wrap_substitutions(String::from("(src: (idx: 1, version: 1), context: ())")).is_err());
+        assert!(wrap_substitutions(String::from("(src: (idx: 1, version: 1)")).is_err());
+        assert!(wrap_substitutions(String::from("(dst: 2, src: (idx: 1, version: 1))")).is_err());
+
+        let error = from_str("(version: 2, state: (src: (idx: 1, version: 1)))").err().unwrap();
+        assert!(matches!(error, ProjectError::Migration(2, _)));
+    }
+
+    /// Rewrites a project saved at the current version as version 1 would have saved it
+    fn downgrade(content: &str) -> String {
+        let mut lines: Vec<String> = Vec::new();
+
+        let mut marks: Option<String> = None;
+        let mut substitution = None;
+
+        let content = content.replacen(&format!("version: {},", FORMAT_VERSION), "version: 1,", 1);
+
+        for line in content.lines() {
+            let trimmed = line.trim_start();
+            let indent = &line[..line.len() - trimmed.len()];
+
+            match marks.take() {
+                // Diacritics were written into the symbol they were added to
+                Some(collected) if trimmed == "]," => {
+                    let symbol = lines
+                        .iter_mut()
+                        .rev()
+                        .find(|line| line.trim_start().starts_with("symbol: \""))
+                        .unwrap();
+
+                    let end = symbol.len() - "\",".len();
+                    symbol.insert_str(end, &collected);
+                },
+                Some(mut collected) => {
+                    let name = trimmed.trim_end_matches(',');
+                    let diacritic = enum_iterator::all::<Diacritic>()
+                        .find(|diacritic| format!("{:?}", diacritic) == name)
+                        .unwrap();
+
+                    collected.push(diacritic.mark());
+                    marks = Some(collected);
+                },
+                None if trimmed == "diacritics: []," => { /*  */ },
+                None if trimmed == "diacritics: [" => marks = Some(String::new()),
+                // Sound changes held their phonemes directly
+                None if trimmed == "change: Substitution(" => substitution = Some(String::from(indent)),
+                None if substitution.as_deref() == Some(indent) && trimmed == ")," => substitution = None,
+                None => lines.push(String::from(line))
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    #[test]
+    fn projects_saved_at_version_1_are_loaded() {
+        let mut state = State::default();
+        let root = state.root;
+
+        // The root charts a dental t, whose mark version 1 kept in its symbol
+        let t = state.ipa.phonemes().find(|id| state.phonemes[*id].symbol == "t").unwrap();
+        let quality = state.ipa.consonants.get_quality(t).unwrap();
+
+        let mut dental = state.phonemes[t].clone();
+        let _ = dental.diacritics.insert(Diacritic::Dental);
+
+        let dental = state.phonemes.insert(dental);
+        state.phonemes[dental].set_id(dental);
+        state.dialects[state.inventory].consonants.add_phoneme(dental, quality);
+
+        let dialect = state.dialects.insert(Language::default());
+        let child = state.language_tree.add_node(dialect);
+        state.language_tree.add_edge(root, child, ());
+
+        let (rules, stages) = parse_rules("t̪ > p / _a\nɑ > ɐ", &state.lineage(root), &state.lineage(child))
+            .unwrap_or_else(|errors| panic!("{:?}", errors));
+
+        state.replace_sound_changes(child, rules, stages);
+
+        // Text that looks like the fields being migrated is left alone
+        let _ = state.dialects[state.inventory].lexicon.insert(Entry { 
+            form: vec![dental], 
+            gloss: String::from("src: (idx: 1, version: 1), dst: (idx: 1, version: 1)"), 
+            notes: String::from("symbol: \"t̪\""), 
+            ..Default::default() 
+        });
+
+        let downgraded = downgrade(&to_string(&state).unwrap());
+
+        assert!(!downgraded.contains("diacritics: [") && !downgraded.contains("change: Substitution("));
+
+        let loaded = from_str(&downgraded).unwrap_or_else(|error| panic!("{}", error));
+
+        // Alphabets are saved in no particular order, so compare what was migrated instead
+        for (id, phoneme) in state.phonemes.iter() {
+            assert!(loaded.phonemes[id] == *phoneme, "/{}/ was migrated differently", phoneme);
+        }
+
+        assert_eq!(
+            print_rules(&loaded.phonemes, &loaded.dialects[dialect]),
+            print_rules(&state.phonemes, &state.dialects[dialect])
+        );
+
+        let entries = |state: &State| state.dialects[state.inventory].lexicon
+            .values()
+            .map(|entry| (entry.form.clone(), entry.gloss.clone(), entry.notes.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(entries(&loaded), entries(&state));
+    }
+
+    #[test]
+    fn projects_saved_before_cells_were_added_to_the_ipa_gain_them() {
+        let mut state = State::default();
+
+        let symbol = |state: &State, id| format!("{}", state.phonemes[id]);
+
+        // Ejectives and clicks were only charted once non-pulmonic consonants were
+        let added = state.ipa
+            .phonemes()
+            .filter(|id| symbol(&state, *id).ends_with('ʼ') || symbol(&state, *id) == "ǃ")
+            .collect::<Vec<_>>();
+
+        for id in added {
+            state.ipa.consonants.remove_phoneme(id);
+            state.phonemes.remove(id);
+        }
+
+        // Cells that are charted differently are moved, as t was once only alveolar
+        let p = state.ipa.phonemes().find(|id| symbol(&state, *id) == "p").unwrap();
+        let t = state.ipa.phonemes().find(|id| symbol(&state, *id) == "t").unwrap();
+        let quality = state.ipa.consonants.get_quality(t).unwrap();
+
+        state.ipa.consonants.remove_phoneme(t);
+        state.ipa.consonants.add_phoneme(t, PhonemeQuality(quality.0.clone(), Rc::from(&[Region::Alveolar][..]), quality.2.clone()));
+
+        let loaded = from_str(&to_string(&state).unwrap()).unwrap();
+        let charted = |text: &str| loaded.ipa.phonemes().find(|id| symbol(&loaded, *id) == text);
+
+        assert!(charted("kʼ").is_some());
+        assert!(charted("ǃ").is_some());
+
+        // Cells that were already charted keep their phonemes
+        assert_eq!(charted("p"), Some(p));
+        assert_eq!(charted("t"), Some(t));
+        assert_eq!(loaded.ipa.consonants.get_quality(t), Some(quality));
+        assert_eq!(loaded.ipa.phonemes().count(), State::default().ipa.phonemes().count());
+    }
+}
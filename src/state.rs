@@ -1,19 +1,26 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
 use std::rc;
 
 use petgraph::stable_graph::{StableGraph, NodeIndex};
 use slotmap::SlotMap;
 
-use crate::pane::LanguagePaneRole;
-use crate::types::{Phoneme, Language, Alphabet, PhonemeQuality, Phone};
+use crate::types::{Phoneme, Language, Alphabet, PhonemeQuality, Phone, Lineage, Word};
+use crate::types::{Rule, RuleChange, SoundChange, Change, Orthography, Prosody, RawQuality, Step};
+use crate::types::{SoundChangeContext, EntryKey, Stage, resolve_shift};
 use crate::types::category;
 
-#[allow(unused_imports)]
-use crate::types::{add_symbol_to_alphabet, CONSONANT, VOWEL};
+use crate::types::add_symbol_to_alphabet;
+
+#[cfg(feature = "gui")]
+use crate::pane::LanguagePaneRole;
 
+/// A phoneme picked from one of the inventory panes
+#[cfg(feature = "gui")]
 #[derive(Clone)]
 pub struct Selection {
     pub phoneme: Phoneme,
-    pub quality: (rc::Rc<[usize]>, rc::Rc<[usize]>, rc::Rc<[usize]>),
+    pub quality: RawQuality,
     pub source: LanguagePaneRole
 }
 
@@ -29,9 +36,11 @@ pub struct State {
     pub invalid: Phoneme,
     pub space: Phoneme,
 
+    #[cfg(feature = "gui")]
     #[serde(skip)]
     pub buffer: Option<Selection>,
 
+    #[cfg(feature = "gui")]
     #[serde(skip)]
     pub buffer_state: bool
 }
@@ -62,12 +71,644 @@ impl Default for State {
             ipa,
             invalid: Phoneme::new("0", Phone::consonant()),
             space: Phoneme::new(" ", Phone::consonant()),
+            #[cfg(feature = "gui")]
             buffer: None,
+            #[cfg(feature = "gui")]
             buffer_state: false
         }
     }
 }
 
+impl State {
+    pub fn parent(&self, id: NodeIndex<u32>) -> Option<NodeIndex<u32>> {
+        self.language_tree
+            .neighbors_directed(id, petgraph::Incoming)
+            .next()
+    }
+
+    /// The node at `id` followed by each of its ancestors, ending at the root
+    pub fn ancestors(&self, id: NodeIndex<u32>) -> Vec<NodeIndex<u32>> {
+        let mut ancestors = vec![id];
+        while let Some(parent) = self.parent(*ancestors.last().unwrap()) {
+            ancestors.push(parent);
+        }
+
+        ancestors
+    }
+
+    /// Every language `id` may have inherited phonemes from, 
+    /// ending with the language at `id` itself
+    pub fn lineage(&self, id: NodeIndex<u32>) -> Lineage<'_> {
+        let mut lineage = Lineage::new(&self.phonemes);
+
+        lineage.push(&self.ipa);
+
+        for ancestor in self.ancestors(id).into_iter().rev() {
+            lineage.push(&self.dialects[self.language_tree[ancestor]]);
+        }
+
+        lineage
+    }
+
+    /// Like `lineage`, but without the IPA, so that only the phonemes 
+    /// of the dialect at `id` and its ancestors are candidates
+    pub fn inventory_lineage(&self, id: NodeIndex<u32>) -> Lineage<'_> {
+        let mut lineage = Lineage::new(&self.phonemes);
+
+        for ancestor in self.ancestors(id).into_iter().rev() {
+            lineage.push(&self.dialects[self.language_tree[ancestor]]);
+        }
+
+        lineage
+    }
+
+    /// The spellings available to the dialect at `id`, 
+    /// including those it inherited from its ancestors
+    pub fn orthography(&self, id: NodeIndex<u32>) -> Orthography {
+        let ids = self.ancestors(id)
+            .into_iter()
+            .flat_map(|ancestor| self.dialects[self.language_tree[ancestor]].phonemes());
+
+        Orthography::new(&self.phonemes, ids)
+    }
+
+    /// Derives the reflex of `word` in the dialect at `id`,
+    /// where `word` belongs to that dialect's parent
+    /// `entry` is the entry of the root's lexicon that the word descends from, if any
+    pub fn derive(
+        &self, 
+        word: &[slotmap::DefaultKey], 
+        prosody: &Prosody, 
+        id: NodeIndex<u32>, 
+        entry: Option<EntryKey>) -> (Word, Prosody) {
+
+        let lineage = self.lineage(id);
+
+        self.dialects[self.language_tree[id]].apply_sound_changes(&lineage, word, prosody, entry)
+    }
+
+    /// The word in the dialect at `id` after each of its sound changes,
+    /// where `word` belongs to that dialect's parent
+    pub fn trace(
+        &self, 
+        word: &[slotmap::DefaultKey], 
+        prosody: &Prosody, 
+        id: NodeIndex<u32>, 
+        entry: Option<EntryKey>) -> Vec<Step> {
+
+        let lineage = self.lineage(id);
+
+        self.dialects[self.language_tree[id]].trace_sound_changes(&lineage, word, prosody, entry)
+    }
+
+    /// Every node of the language tree in pre-order, starting at the root
+    pub fn dialect_order(&self) -> Vec<NodeIndex<u32>> {
+        self.subtree(self.root)
+    }
+
+    /// The node at `id` followed by all of its descendants, in pre-order
+    pub fn subtree(&self, id: NodeIndex<u32>) -> Vec<NodeIndex<u32>> {
+        let mut order = Vec::new();
+
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            let children = self.language_tree
+                .neighbors_directed(id, petgraph::Outgoing)
+                .collect::<Vec<_>>();
+
+            stack.extend(children.into_iter().rev());
+
+            order.push(id);
+        }
+
+        order
+    }
+
+    /// Every phoneme that a word of some dialect's lexicon is written with
+    /// These can't be removed from an inventory without leaving gaps in the words
+    pub fn lexicon_phonemes(&self) -> HashSet<slotmap::DefaultKey> {
+        self.dialects
+            .values()
+            .flat_map(|dialect| dialect.lexicon.values())
+            .flat_map(|entry| entry.form.iter().copied())
+            .collect()
+    }
+
+    /// Removes the dialect at `id` along with all of its descendants,
+    /// reclaiming their languages and every phoneme they own, sound change products included
+    /// Other dialects that still refer to those phonemes are pointed elsewhere first, see `rebind`
+    /// The selected language falls back to the dialect's parent if it was removed
+    /// The root language can't be removed
+    pub fn remove_dialect(&mut self, id: NodeIndex<u32>) {
+        let parent = match self.parent(id) {
+            Some(parent) => parent,
+            None => return
+        };
+
+        let subtree = self.subtree(id);
+
+        let owned = subtree
+            .iter()
+            .flat_map(|node| self.dialects[self.language_tree[*node]].phonemes())
+            .collect::<Vec<_>>();
+
+        let stale = self.charted(owned.iter().copied());
+
+        for node in subtree {
+            let _ = self.language_tree
+                .remove_node(node)
+                .and_then(|key| self.dialects.remove(key));
+
+            if self.inventory_index == node {
+                self.inventory = self.language_tree[parent];
+                self.inventory_index = parent;
+            }
+        }
+
+        for node in self.dialect_order() {
+            self.rebind(node, &stale);
+        }
+
+        for phoneme in owned {
+            self.phonemes.remove(phoneme);
+        }
+    }
+
+    /// The children of the node at `id`, in the order they're shown
+    pub fn children(&self, id: NodeIndex<u32>) -> Vec<NodeIndex<u32>> {
+        self.language_tree
+            .neighbors_directed(id, petgraph::Outgoing)
+            .collect()
+    }
+
+    /// Reconnects `children` to `parent` so that they're listed in the given order
+    fn set_children(&mut self, parent: NodeIndex<u32>, children: Vec<NodeIndex<u32>>) {
+        for child in children.iter() {
+            if let Some(edge) = self.language_tree.find_edge(parent, *child) {
+                self.language_tree.remove_edge(edge);
+            }
+        }
+
+        // Neighbors are listed starting with the most recently added edge
+        for child in children.into_iter().rev() {
+            self.language_tree.add_edge(parent, child, ());
+        }
+    }
+
+    /// Moves the dialect at `id` under `parent`, at `position` among the parent's other children
+    /// A dialect can't be moved under itself or any of its descendants, and the root can't be moved at all
+    /// Whatever referred to phonemes of the old lineage is pointed at the phonemes that sound the same in the new one,
+    /// and phonemes the new lineage lacks are copied into the moved dialects' own inventories, see `rebind`
+    /// Shifts are then resolved against the new lineage, see `resolve_shifts`
+    pub fn move_dialect(&mut self, id: NodeIndex<u32>, parent: NodeIndex<u32>, position: usize) {
+        let subtree = self.subtree(id);
+
+        let old_parent = match self.parent(id) {
+            Some(old_parent) if !subtree.contains(&parent) => old_parent,
+            _ => return
+        };
+
+        let edge = self.language_tree.find_edge(old_parent, id).unwrap();
+        self.language_tree.remove_edge(edge);
+
+        let mut children = self.children(parent);
+        children.insert(position.min(children.len()), id);
+
+        self.set_children(parent, children);
+
+        if old_parent == parent { return; }
+
+        for node in subtree {
+            let inherited = self.inherited(node);
+
+            let stale = self.dialects[self.language_tree[node]]
+                .references()
+                .into_iter()
+                .filter(|reference| !inherited.contains(reference));
+
+            let stale = self.charted(stale);
+
+            self.rebind(node, &stale);
+        }
+
+        self.resolve_shifts(id);
+    }
+
+    /// Copies the dialect at `id` into a new sibling placed just after it, see `Language::duplicate`
+    /// The dialect's descendants aren't copied along with it
+    /// Returns the node of the copy, or `None` for the root
+    pub fn duplicate_dialect(&mut self, id: NodeIndex<u32>) -> Option<NodeIndex<u32>> {
+        let parent = self.parent(id)?;
+
+        let language = self.dialects[self.language_tree[id]].duplicate(&mut self.phonemes);
+
+        let copy = self.dialects.insert(language);
+        let copy = self.language_tree.add_node(copy);
+
+        let mut children = self.children(parent);
+        let position = children.iter().position(|child| *child == id).unwrap();
+
+        children.insert(position + 1, copy);
+
+        self.set_children(parent, children);
+
+        Some(copy)
+    }
+
+    /// Removes the languages that are no longer part of the language tree, along with their phonemes
+    /// Dialects were once deleted without them, so older projects may still carry some
+    pub fn remove_orphans(&mut self) {
+        let orphans = self.dialects
+            .keys()
+            .filter(|key| !self.language_tree.node_weights().any(|node| node == key))
+            .collect::<Vec<_>>();
+
+        for key in orphans {
+            let language = self.dialects.remove(key).unwrap();
+
+            for phoneme in language.phonemes() {
+                self.phonemes.remove(phoneme);
+            }
+        }
+    }
+
+    /// Charts the cells that the IPA gained since the project was saved, 
+    /// and moves any that it has since charted differently
+    /// Cells that were already charted keep their phonemes, since dialects may refer to them
+    pub fn merge_ipa(&mut self) {
+        let mut phonemes = SlotMap::new();
+        let ipa = init_ipa(&mut phonemes);
+
+        merge_alphabet(&mut self.phonemes, &mut self.ipa.consonants, &phonemes, &ipa.consonants);
+        merge_alphabet(&mut self.phonemes, &mut self.ipa.vowels, &phonemes, &ipa.vowels);
+    }
+
+    /// Derives the reflex of a word from the root language in every dialect,
+    /// running each edge's sound changes on the way down the tree
+    /// Reflexes are listed in the same order as `dialect_order`
+    pub fn propagate(
+        &self, 
+        word: &[slotmap::DefaultKey], 
+        prosody: &Prosody, 
+        entry: Option<EntryKey>) -> Vec<(NodeIndex<u32>, Word, Prosody)> {
+        let mut reflexes = Vec::new();
+
+        let mut stack = vec![(self.root, word.to_vec(), prosody.clone())];
+        while let Some((id, word, prosody)) = stack.pop() {
+            let children = self.language_tree
+                .neighbors_directed(id, petgraph::Outgoing)
+                .collect::<Vec<_>>();
+
+            for child in children.into_iter().rev() {
+                let (reflex, prosody) = self.derive(&word, &prosody, child, entry);
+
+                stack.push((child, reflex, prosody));
+            }
+
+            reflexes.push((id, word, prosody));
+        }
+
+        reflexes
+    }
+
+    /// Copies a sound change's product into the inventory of the dialect at `id`,
+    /// so that every sound change owns its destination phonemes
+    fn add_product(&mut self, id: NodeIndex<u32>, product: Phoneme, quality: RawQuality) -> slotmap::DefaultKey {
+        let product = {
+            let id = self.phonemes.insert(product);
+
+            self.phonemes[id].set_id(id);
+            id
+        };
+
+        let dialect = &mut self.dialects[self.language_tree[id]];
+
+        if self.phonemes[product].phone.is_consonant() {
+            dialect.consonants.add_phoneme(product, PhonemeQuality::from_raw(quality));
+        } else if self.phonemes[product].phone.is_vowel() {
+            dialect.vowels.add_phoneme(product, PhonemeQuality::from_raw(quality));
+        } else if self.phonemes[product].phone.is_diphthong() {
+            dialect.diphthongs.push(product);
+        } else {
+            unreachable!();
+        }
+
+        product
+    }
+
+    /// Takes a product out of the inventory of the dialect at `id`, leaving the phoneme itself in place
+    fn detach_product(&mut self, id: NodeIndex<u32>, product: slotmap::DefaultKey) {
+        let dialect = &mut self.dialects[self.language_tree[id]];
+
+        if dialect.consonants.get_quality(product).is_some() {
+            dialect.consonants.remove_phoneme(product);
+        } else if dialect.vowels.get_quality(product).is_some() {
+            dialect.vowels.remove_phoneme(product);
+        } else {
+            dialect.diphthongs.retain(|id| *id != product);
+        }
+    }
+
+    /// The phonemes charted by the dialect at `id` and by each of its ancestors, nearest first
+    fn inherited(&self, id: NodeIndex<u32>) -> Vec<slotmap::DefaultKey> {
+        self.ancestors(id)
+            .into_iter()
+            .flat_map(|ancestor| self.dialects[self.language_tree[ancestor]].phonemes())
+            .collect()
+    }
+
+    /// Each of `ids` that some dialect charts, along with the quality it's charted with
+    fn charted(&self, ids: impl IntoIterator<Item = slotmap::DefaultKey>) -> HashMap<slotmap::DefaultKey, (Phoneme, RawQuality)> {
+        ids.into_iter()
+            .filter_map(|id| {
+                let phoneme = self.phonemes.get(id)?;
+
+                let quality = self.dialects.values().find_map(|dialect| {
+                    dialect.consonants.get_quality(id).map(|quality| quality.into_raw())
+                        .or_else(|| dialect.vowels.get_quality(id).map(|quality| quality.into_raw()))
+                        // Diphthongs carry their own qualities
+                        .or_else(|| dialect.diphthongs
+                            .contains(&id)
+                            .then(|| PhonemeQuality::<category::Constriction, category::Place, category::Rounding>::blank().into_raw()))
+                })?;
+
+                Some((id, (phoneme.clone(), quality)))
+            })
+            .collect()
+    }
+
+    /// Points whatever the dialect at `id` refers to among `stale` at a phoneme it inherits that sounds the same,
+    /// copying the phoneme into the dialect's own inventory where none does
+    fn rebind(&mut self, id: NodeIndex<u32>, stale: &HashMap<slotmap::DefaultKey, (Phoneme, RawQuality)>) {
+        let mut references = self.dialects[self.language_tree[id]]
+            .references()
+            .into_iter()
+            .filter(|reference| stale.contains_key(reference))
+            .collect::<Vec<_>>();
+
+        if references.is_empty() { return; }
+
+        references.sort();
+        references.dedup();
+
+        let mut map = HashMap::new();
+        for reference in references {
+            let (phoneme, quality) = &stale[&reference];
+
+            let found = self.inherited(id)
+                .into_iter()
+                .filter(|candidate| !stale.contains_key(candidate))
+                .find(|candidate| self.phonemes.get(*candidate).map_or(false, |candidate| {
+                    candidate.phone == phoneme.phone && format!("{}", candidate) == format!("{}", phoneme)
+                }));
+
+            let rebound = match found {
+                Some(found) => found,
+                None => self.add_product(id, phoneme.clone(), quality.clone())
+            };
+
+            map.insert(reference, rebound);
+        }
+
+        self.dialects[self.language_tree[id]].remap(|id| map.get(&id).copied().unwrap_or(id));
+    }
+
+    /// Removes products of the dialect at `id`, once whatever the dialect 
+    /// and its descendants refer to them with has been pointed elsewhere, see `rebind`
+    fn reclaim_products(&mut self, id: NodeIndex<u32>, products: Vec<slotmap::DefaultKey>) {
+        let stale = self.charted(products.iter().copied());
+
+        for product in products.iter() {
+            self.detach_product(id, *product);
+        }
+
+        for node in self.subtree(id) {
+            self.rebind(node, &stale);
+        }
+
+        for product in products {
+            self.phonemes.remove(product);
+        }
+    }
+
+    /// Resolves what each phoneme inherited by the dialect at `id` becomes under a shift
+    /// Phonemes that don't meet `src`, or that no cell of the IPA fits, are left out
+    fn resolve_products(
+        &self, 
+        id: NodeIndex<u32>, 
+        src: &SoundChangeContext, 
+        dst: &SoundChangeContext) -> Vec<(slotmap::DefaultKey, Phoneme, RawQuality)> {
+
+        let parent = match self.parent(id) {
+            Some(parent) => parent,
+            None => return Vec::new()
+        };
+
+        let lineage = self.lineage(parent);
+
+        let mut inherited = self.ancestors(parent)
+            .into_iter()
+            .flat_map(|ancestor| self.dialects[self.language_tree[ancestor]].phonemes())
+            .collect::<Vec<_>>();
+
+        inherited.sort();
+        inherited.dedup();
+
+        inherited
+            .into_iter()
+            .filter(|phoneme| src.selects(&lineage, *phoneme))
+            .filter_map(|phoneme| {
+                let (product, quality) = resolve_shift(&lineage, &self.ipa, phoneme, dst)?;
+
+                let unchanged = product.phone == self.phonemes[phoneme].phone //
+                    && format!("{}", product) == format!("{}", self.phonemes[phoneme]);
+
+                (!unchanged).then_some((phoneme, product, quality))
+            })
+            .collect()
+    }
+
+    /// Adds a sound change to the end of the dialect at `id`
+    /// The rule's products are copied into the dialect's inventory, 
+    /// resolving those of a shift against the phonemes the dialect inherits
+    pub fn add_sound_change(&mut self, id: NodeIndex<u32>, rule: Rule) {
+        let Rule { change, context, prosody, application } = rule;
+
+        let change = match change {
+            RuleChange::Substitution { src, dst, quality } => {
+                let dst = self.add_product(id, dst, quality);
+
+                Change::Substitution { src, dst }
+            },
+            RuleChange::Shift { src, dst } => {
+                let products = self.resolve_products(id, &src, &dst)
+                    .into_iter()
+                    .map(|(phoneme, product, quality)| (phoneme, self.add_product(id, product, quality)))
+                    .collect();
+
+                Change::Shift { src, dst, products }
+            },
+            RuleChange::Sequence { src, dst } => {
+                let dst = dst
+                    .into_iter()
+                    .map(|(dst, quality)| self.add_product(id, dst, quality))
+                    .collect();
+
+                Change::Sequence { src, dst }
+            },
+            RuleChange::Metathesis { src } => Change::Metathesis { src },
+            RuleChange::Gemination { src } => Change::Gemination { src },
+            RuleChange::Degemination { src } => Change::Degemination { src }
+        };
+
+        let dialect = &mut self.dialects[self.language_tree[id]];
+
+        dialect.sound_changes.push(SoundChange { change, context, prosody, application });
+    }
+
+    /// Removes a sound change along with the phonemes it produced, see `reclaim_products`
+    pub fn remove_sound_change(&mut self, id: NodeIndex<u32>, idx: usize) {
+        let dialect = &mut self.dialects[self.language_tree[id]];
+
+        let SoundChange { change, .. } = dialect.sound_changes.remove(idx);
+
+        dialect.forget_sound_change(idx);
+
+        self.reclaim_products(id, change.products());
+    }
+
+    /// Replaces the sound changes and stages of the dialect at `id` with those read from text
+    /// Sound changes that a rule still describes are kept as they were, products and exceptions included,
+    /// and the products of the others are reclaimed, see `reclaim_products`
+    pub fn replace_sound_changes(&mut self, id: NodeIndex<u32>, rules: Vec<Rule>, stages: Vec<Stage>) {
+        let dialect = &mut self.dialects[self.language_tree[id]];
+
+        let mut existing = mem::take(&mut dialect.sound_changes)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+
+        dialect.stages = stages;
+
+        for rule in rules {
+            let kept = existing
+                .iter_mut()
+                .find(|sound_change| sound_change
+                    .as_ref()
+                    .map_or(false, |sound_change| rule.describes(&self.phonemes, sound_change)))
+                .and_then(Option::take);
+
+            match kept {
+                Some(sound_change) => self.dialects[self.language_tree[id]].sound_changes.push(sound_change),
+                None => self.add_sound_change(id, rule)
+            }
+        }
+
+        let products = existing
+            .into_iter()
+            .flatten()
+            .flat_map(|sound_change| sound_change.change.products())
+            .collect();
+
+        self.reclaim_products(id, products);
+
+        self.resolve_shifts(id);
+    }
+
+    /// Resolves the shifts of the dialect at `id` and of its descendants again,
+    /// since what they inherit may have changed since they were added
+    /// Products that a phoneme still shifts to are kept, and the rest are reclaimed, see `reclaim_products`
+    pub fn resolve_shifts(&mut self, id: NodeIndex<u32>) {
+        for node in self.subtree(id) {
+            let count = self.dialects[self.language_tree[node]].sound_changes.len();
+
+            for idx in 0..count {
+                let (src, dst, mut existing) = match &self.dialects[self.language_tree[node]].sound_changes[idx].change {
+                    Change::Shift { src, dst, products } => (src.clone(), dst.clone(), products.clone()),
+                    _ => continue
+                };
+
+                let mut products = Vec::new();
+                for (phoneme, product, quality) in self.resolve_products(node, &src, &dst) {
+                    let kept = existing.iter().position(|(other, existing)| {
+                        *other == phoneme && self.phonemes.get(*existing).map_or(false, |existing| {
+                            existing.phone == product.phone && format!("{}", existing) == format!("{}", product)
+                        })
+                    });
+
+                    match kept {
+                        Some(kept) => products.push(existing.swap_remove(kept)),
+                        None => products.push((phoneme, self.add_product(node, product, quality)))
+                    }
+                }
+
+                if let Change::Shift { products: shifted, .. } = &mut self.dialects[self.language_tree[node]].sound_changes[idx].change {
+                    *shifted = products;
+                }
+
+                self.reclaim_products(node, existing.into_iter().map(|(_, product)| product).collect());
+            }
+        }
+    }
+
+    /// Adds a diphthong that glides between two vowels of the IPA to the dialect at `id`
+    /// Its symbol is the vowels' symbols written together
+    pub fn add_diphthong(&mut self, id: NodeIndex<u32>, start: slotmap::DefaultKey, end: slotmap::DefaultKey) {
+        let quality = |vowel: slotmap::DefaultKey| {
+            let PhonemeQuality(a, b, c) = self.ipa.vowels.get_quality(vowel).unwrap();
+
+            (a[0], b[0], c[0])
+        };
+
+        let symbol = format!("{}{}", self.phonemes[start], self.phonemes[end]);
+        let phone = Phone::diphthong(quality(start), quality(end));
+
+        let diphthong = self.phonemes.insert(Phoneme::new(symbol, phone));
+
+        self.phonemes[diphthong].set_id(diphthong);
+
+        self.dialects[self.language_tree[id]].diphthongs.push(diphthong);
+    }
+
+    /// Removes a phoneme charted by the dialect at `id`, 
+    /// once whatever the dialect and its descendants refer to it with has been pointed elsewhere, see `rebind`
+    pub fn remove_phoneme(&mut self, id: NodeIndex<u32>, phoneme: slotmap::DefaultKey) {
+        self.reclaim_products(id, vec![phoneme]);
+    }
+}
+
+/// Brings `saved` in line with `current`, matching their phonemes by symbol
+fn merge_alphabet<A, B, C>(
+    phonemes: &mut SlotMap<slotmap::DefaultKey, Phoneme>,
+    saved: &mut Alphabet<A, B, C>,
+    current_phonemes: &SlotMap<slotmap::DefaultKey, Phoneme>,
+    current: &Alphabet<A, B, C>) where
+    A: category::Outer<B, C>,
+    B: category::Inner<C>,
+    C: category::Pair {
+
+    let charted = saved
+        .phonemes()
+        .filter_map(|id| phonemes.get(id).map(|phoneme| (format!("{}", phoneme), id)))
+        .collect::<HashMap<_, _>>();
+
+    for (id, quality) in current.phoneme_qualities() {
+        match charted.get(&format!("{}", current_phonemes[id])) {
+            Some(charted) if saved.get_quality(*charted).as_ref() == Some(&quality) => { /*  */ },
+            Some(charted) => {
+                saved.remove_phoneme(*charted);
+                saved.add_phoneme(*charted, quality);
+            },
+            None => {
+                let id = phonemes.insert(current_phonemes[id].clone());
+
+                phonemes[id].set_id(id);
+                saved.add_phoneme(id, quality);
+            }
+        }
+    }
+}
+
 fn init_ipa(phonemes: &mut SlotMap<slotmap::DefaultKey, Phoneme>) -> Language {
     let mut vowels = Alphabet::new();
 
@@ -79,7 +720,7 @@ fn init_ipa(phonemes: &mut SlotMap<slotmap::DefaultKey, Phoneme>) -> Language {
         let mut add = |
             symbol: &'static str, 
             quality: PhonemeQuality<Constriction, Place, Rounding>| {
-            add_symbol_to_alphabet(phonemes, &mut vowels, symbol, VOWEL, quality);
+            add_symbol_to_alphabet(phonemes, &mut vowels, symbol, Phone::vowel(), quality);
         };
 
         add("i", (Close, Front, Unrounded).into());
@@ -122,7 +763,7 @@ fn init_ipa(phonemes: &mut SlotMap<slotmap::DefaultKey, Phoneme>) -> Language {
         let mut add = |
             symbol: &'static str, 
             quality: PhonemeQuality<Articulation, Region, Voicing>| {
-            add_symbol_to_alphabet(phonemes, &mut consonants, symbol, CONSONANT, quality);
+            add_symbol_to_alphabet(phonemes, &mut consonants, symbol, Phone::consonant(), quality);
         };
 
         add("p", (Plosive, Bilabial, Voiceless).into());
@@ -151,6 +792,7 @@ fn init_ipa(phonemes: &mut SlotMap<slotmap::DefaultKey, Phoneme>) -> Language {
         add("ⱱ", (Flap, Labiodental, Voiced).into());
         add("ɾ", (Flap, &[Alveolar, Post][..], Voiced).into());
         add("ɽ", (Flap, Retroflex, Voiced).into());
+        add("ɺ", (LatFlap, &[Alveolar, Post][..], Voiced).into());
 
         add("ɸ", (Fricative, Bilabial, Voiceless).into());
         add("β", (Fricative, Bilabial, Voiced).into());
@@ -187,7 +829,180 @@ fn init_ipa(phonemes: &mut SlotMap<slotmap::DefaultKey, Phoneme>) -> Language {
         add("ɭ", (LatApproximant, Retroflex, Voiced).into());
         add("ʎ", (LatApproximant, Palatal, Voiced).into());
         add("ʟ", (LatApproximant, Velar, Voiced).into());
+
+        add("ʘ", (Click, Bilabial, Voiceless).into());
+        add("ǀ", (Click, Dental, Voiceless).into());
+        add("ǃ", (Click, &[Alveolar, Post][..], Voiceless).into());
+        add("ǂ", (Click, Palatal, Voiceless).into());
+        add("ǁ", (LatClick, &[Alveolar, Post][..], Voiceless).into());
+
+        add("ɓ", (Implosive, Bilabial, Voiced).into());
+        add("ɗ", (Implosive, &[Dental, Alveolar][..], Voiced).into());
+        add("ʄ", (Implosive, Palatal, Voiced).into());
+        add("ɠ", (Implosive, Velar, Voiced).into());
+        add("ʛ", (Implosive, Uvular, Voiced).into());
+
+        add("pʼ", (Ejective, Bilabial, Voiceless).into());
+        add("tʼ", (Ejective, &[Dental, Alveolar][..], Voiceless).into());
+        add("ʈʼ", (Ejective, Retroflex, Voiceless).into());
+        add("cʼ", (Ejective, Palatal, Voiceless).into());
+        add("kʼ", (Ejective, Velar, Voiceless).into());
+        add("qʼ", (Ejective, Uvular, Voiceless).into());
+
+        add("fʼ", (EjFricative, Labiodental, Voiceless).into());
+        add("θʼ", (EjFricative, Dental, Voiceless).into());
+        add("sʼ", (EjFricative, Alveolar, Voiceless).into());
+        add("ʃʼ", (EjFricative, Post, Voiceless).into());
+        add("ʂʼ", (EjFricative, Retroflex, Voiceless).into());
+        add("xʼ", (EjFricative, Velar, Voiceless).into());
+        add("χʼ", (EjFricative, Uvular, Voiceless).into());
+
+        add("ɕ", (Fricative, AlveoloPalatal, Voiceless).into());
+        add("ʑ", (Fricative, AlveoloPalatal, Voiced).into());
+        add("ʡ", (Plosive, Epiglottal, Voiceless).into());
+        add("ʜ", (Fricative, Epiglottal, Voiceless).into());
+        add("ʢ", (Fricative, Epiglottal, Voiced).into());
+
+        add("k\u{0361}p", (Plosive, LabialVelar, Voiceless).into());
+        add("g\u{0361}b", (Plosive, LabialVelar, Voiced).into());
+        add("ŋ\u{0361}m", (Nasal, LabialVelar, Voiced).into());
+        add("ʍ", (Fricative, LabialVelar, Voiceless).into());
+        add("w", (Approximant, LabialVelar, Voiced).into());
+        add("ɥ", (Approximant, LabialPalatal, Voiced).into());
+        add("ɧ", (Fricative, PostVelar, Voiceless).into());
     }    
 
-    Language { name: rc::Rc::from("IPA"), vowels, consonants, sound_changes: Vec::new() }
-}
\ No newline at end of file
+    Language { name: rc::Rc::from("IPA"), vowels, consonants, ..Default::default() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{parse_rules, parse_word, word_to_string};
+
+    fn chart(state: &mut State, node: NodeIndex<u32>, text: &str) {
+        let ipa = parse_word(text, state.ipa.phonemes().map(|id| (id, format!("{}", state.phonemes[id])))).unwrap();
+
+        for source in ipa {
+            let phoneme = state.phonemes[source].clone();
+            let id = state.phonemes.insert(phoneme);
+            state.phonemes[id].set_id(id);
+
+            let dialect = &mut state.dialects[state.language_tree[node]];
+            match state.ipa.consonants.get_quality(source) {
+                Some(quality) => dialect.consonants.add_phoneme(id, quality),
+                None => dialect.vowels.add_phoneme(id, state.ipa.vowels.get_quality(source).unwrap())
+            }
+        }
+    }
+
+    fn dialect(state: &mut State, parent: NodeIndex<u32>) -> NodeIndex<u32> {
+        let key = state.dialects.insert(Language::default());
+        let node = state.language_tree.add_node(key);
+        let _ = state.language_tree.add_edge(parent, node, ());
+
+        node
+    }
+
+    fn products(state: &State, node: NodeIndex<u32>) -> Vec<String> {
+        let mut products = state.dialects[state.language_tree[node]]
+            .phonemes()
+            .map(|id| format!("{}", state.phonemes[id]))
+            .collect::<Vec<_>>();
+
+        products.sort();
+        products
+    }
+
+    fn apply(state: &mut State, node: NodeIndex<u32>, text: &str) {
+        let source = state.lineage(state.parent(node).unwrap());
+        let target = state.lineage(node);
+
+        let (rules, stages) = parse_rules(text, &source, &target).unwrap();
+        state.replace_sound_changes(node, rules, stages);
+    }
+
+    #[test]
+    fn shifts_are_resolved_again_when_reapplied() {
+        let mut state = State::default();
+        let root = state.root;
+        chart(&mut state, root, "pa");
+
+        let child = dialect(&mut state, root);
+        apply(&mut state, child, "[Plosive Voiceless] > [Voiced]");
+        assert_eq!(products(&state, child), ["b"]);
+
+        // The shift was resolved before the root had a t, so it doesn't voice it until it's applied again
+        chart(&mut state, root, "t");
+        assert_eq!(products(&state, child), ["b"]);
+
+        let b = state.dialects[state.language_tree[child]].phonemes().next().unwrap();
+
+        apply(&mut state, child, "[Plosive Voiceless] > [Voiced]");
+        assert_eq!(products(&state, child), ["b", "d"]);
+        assert!(state.dialects[state.language_tree[child]].phonemes().any(|id| id == b));
+
+        let word = parse_word("pata", state.lineage(root).candidates()).unwrap();
+        let (derived, _) = state.derive(&word, &Prosody::default(), child, None);
+        assert_eq!(word_to_string(&state.phonemes, &derived), "bada");
+    }
+
+    #[test]
+    fn removed_phonemes_are_copied_where_they_are_still_referred_to() {
+        let mut state = State::default();
+        let root = state.root;
+        chart(&mut state, root, "pa");
+
+        let child = dialect(&mut state, root);
+        apply(&mut state, child, "p > f");
+
+        let p = parse_word("p", state.lineage(root).candidates()).unwrap()[0];
+        state.remove_phoneme(root, p);
+
+        assert!(!state.phonemes.contains_key(p));
+        assert_eq!(products(&state, root), ["a"]);
+        assert_eq!(products(&state, child), ["f", "p"]);
+
+        let src = match state.dialects[state.language_tree[child]].sound_changes[0].change {
+            Change::Substitution { src, .. } => src,
+            _ => unreachable!()
+        };
+
+        assert!(state.dialects[state.language_tree[child]].phonemes().any(|id| id == src));
+    }
+
+    #[test]
+    fn moved_dialects_copy_the_phonemes_their_new_parent_lacks() {
+        let mut state = State::default();
+        let root = state.root;
+        chart(&mut state, root, "a");
+
+        let a = dialect(&mut state, root);
+        chart(&mut state, a, "k");
+
+        let b = dialect(&mut state, a);
+        apply(&mut state, b, "k > x / a_");
+
+        let c = dialect(&mut state, b);
+        apply(&mut state, c, "k > g");
+
+        let k = parse_word("k", state.lineage(a).candidates()).unwrap()[0];
+
+        state.move_dialect(b, root, 1);
+        assert_eq!(state.parent(b), Some(root));
+
+        // The k of the old parent is left alone, and only the moved dialect needs a copy of it
+        assert!(state.dialects[state.language_tree[a]].phonemes().any(|id| id == k));
+        assert_eq!(products(&state, b), ["k", "x"]);
+        assert_eq!(products(&state, c), ["g"]);
+        assert!(!state.dialects[state.language_tree[b]].references().contains(&k));
+        assert!(!state.dialects[state.language_tree[c]].references().contains(&k));
+
+        let word = parse_word("aka", state.lineage(b).candidates()).unwrap();
+        let (derived, _) = state.derive(&word, &Prosody::default(), b, None);
+        assert_eq!(word_to_string(&state.phonemes, &derived), "axa");
+
+        let (derived, _) = state.derive(&word, &Prosody::default(), c, None);
+        assert_eq!(word_to_string(&state.phonemes, &derived), "aga");
+    }
+}
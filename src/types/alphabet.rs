@@ -1,13 +1,11 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::mem;
 
 use enum_map::EnumMap;
 use slotmap::{DefaultKey, SlotMap};
 
 use crate::types::category::{Outer, Inner, Pair};
-use crate::types::{PhonemeQuality, PhonemeSelector, Phoneme, Phone};
-use crate::types::{CONSONANT, VOWEL};
+use crate::types::{PhonemeQuality, Phoneme, Phone};
 
 pub struct Alphabet<A, B, C> 
     where A: Outer<B, C>, B: Inner<C>, C: Pair {
@@ -51,6 +49,14 @@ impl<'de, A, B, C> serde::Deserialize<'de> for Alphabet<A, B, C>
     }
 }
 
+impl<A, B, C> Default for Alphabet<A, B, C> 
+    where A: Outer<B, C>, B: Inner<C>, C: Pair {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<A, B, C> Alphabet<A, B, C> 
     where A: Outer<B, C>, B: Inner<C>, C: Pair {
     
@@ -70,9 +76,12 @@ impl<A, B, C> Alphabet<A, B, C>
     }
 
     pub fn remove_phoneme(&mut self, id: DefaultKey) {
-        if let Some(quality) = self.get_quality(id.clone()) {
+        if let Some(quality) = self.get_quality(id) {
+            // Another phoneme may have since been charted in the same cell
             for (a, b, c) in quality.into_iter() {
-                self.query[a][b][c] = None;
+                if self.query[a][b][c] == Some(id) {
+                    self.query[a][b][c] = None;
+                }
             }
 
             self.quality.remove(&id);
@@ -87,7 +96,7 @@ impl<A, B, C> Alphabet<A, B, C>
         self.query[a][b][c]
     }
 
-    pub fn select_phonemes(&self, query: PhonemeSelector<A, B, C>) -> impl Iterator<Item = DefaultKey> + '_ {
+    pub fn select_phonemes(&self, query: PhonemeQuality<A, B, C>) -> impl Iterator<Item = DefaultKey> + '_ {
         self.quality
             .keys()
             .filter(move |&id| self.meets_restrictions(*id, query.clone()))
@@ -104,7 +113,7 @@ impl<A, B, C> Alphabet<A, B, C>
     /// This function returns truthy
     /// Empty slices are shorthand for 'all variants'
     /// Because you would never search for an 
-    pub fn meets_restrictions(&self, id: DefaultKey, restriction: PhonemeSelector<A, B, C>) -> bool {
+    pub fn meets_restrictions(&self, id: DefaultKey, restriction: PhonemeQuality<A, B, C>) -> bool {
         let quality = match self.get_quality(id) {
             Some(quality) => quality,
             None => panic!()
@@ -119,7 +128,7 @@ impl<A, B, C> Alphabet<A, B, C>
 
     pub fn phoneme_qualities(&self) -> impl Iterator<Item = (DefaultKey, PhonemeQuality<A, B, C>)> + '_ {
         self.quality.iter().map(|(id, quality)| 
-            (id.clone(), quality.clone()))
+            (*id, quality.clone()))
     }
 }
 
@@ -127,20 +136,12 @@ pub fn add_symbol_to_alphabet<'a, A, B, C>(
     phonemes: &mut SlotMap<slotmap::DefaultKey, Phoneme>, 
     alphabet: &mut Alphabet<A, B, C>,
     symbol: impl Into<Cow<'a, str>>,
-    phone: mem::Discriminant<Phone>,
+    phone: Phone,
     quality: impl Into<PhonemeQuality<A, B, C>>) where
     A: Outer<B, C>,
     B: Inner<C>,
     C: Pair {
 
-    let phone = if phone == CONSONANT {
-        Phone::consonant()
-    } else if phone == VOWEL {
-        Phone::vowel()
-    } else {
-        panic!();
-    };
-
     let phoneme = Phoneme::new(symbol, phone);
     
     // First, get the id
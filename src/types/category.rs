@@ -28,10 +28,6 @@ pub trait Outer<A, B>: Category + //
     EnumArray<EnumMap<A, EnumMap<B, Option<slotmap::DefaultKey>>>> 
     where A: Inner<B>, B: Pair { /*  */ }
 
-pub trait CategoryColor {
-    fn as_color(&self) -> egui::Color32;
-}
-
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[derive(Sequence, Enum)]
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -40,10 +36,16 @@ pub enum Articulation {
     Nasal,
     Trill,
     Flap,
+    LatFlap,
     Fricative,
     LatFricative,
     Approximant,
-    LatApproximant
+    LatApproximant,
+    Click,
+    LatClick,
+    Implosive,
+    Ejective,
+    EjFricative
 }
 
 impl fmt::Display for Articulation {
@@ -54,14 +56,33 @@ impl fmt::Display for Articulation {
             Nasal => "Nasal",
             Trill => "Trill",
             Flap => "Flap (Tap)",
+            LatFlap => "Lat. Flap",
             Fricative => "Fricative",
             LatFricative => "Lat. Fricative",
             Approximant => "Approximant",
-            LatApproximant => "Lat. Approximant"
+            LatApproximant => "Lat. Approximant",
+            Click => "Click",
+            LatClick => "Lat. Click",
+            Implosive => "Implosive",
+            Ejective => "Ejective",
+            EjFricative => "Ej. Fricative"
         })
     }
 }
 
+impl Articulation {
+    /// Whether the sound is made with air pushed out from the lungs
+    /// Clicks, implosives and ejectives belong on the non-pulmonic chart
+    pub fn is_pulmonic(&self) -> bool {
+        !matches!(self, 
+            Articulation::Click | 
+            Articulation::LatClick | 
+            Articulation::Implosive | 
+            Articulation::Ejective | 
+            Articulation::EjFricative)
+    }
+}
+
 impl Outer<Region, Voicing> for Articulation { /*  */ }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -73,12 +94,17 @@ pub enum Region {
     Dental,
     Alveolar,
     Post,
+    AlveoloPalatal,
     Retroflex,
     Palatal,
     Velar,
     Uvular,
     Pharyngeal,
-    Glottal
+    Epiglottal,
+    Glottal,
+    LabialPalatal,
+    LabialVelar,
+    PostVelar
 }
 
 impl fmt::Display for Region {
@@ -90,16 +116,58 @@ impl fmt::Display for Region {
             Dental => "Dental",
             Alveolar => "Alveolar",
             Post => "Post",
+            AlveoloPalatal => "Alveolo-Palatal",
             Retroflex => "Retroflex",
             Palatal => "Palatal",
             Velar => "Velar",
             Uvular => "Uvular",
             Pharyngeal => "Pharyngeal",
-            Glottal => "Glottal"
+            Epiglottal => "Epiglottal",
+            Glottal => "Glottal",
+            LabialPalatal => "Labial-Palatal",
+            LabialVelar => "Labial-Velar",
+            PostVelar => "Post-Velar"
         })
     }
 }
 
+impl Region {
+    /// The places of articulation that are made at the same time
+    /// Every place but a co-articulated one is made on its own
+    pub fn places(&self) -> &'static [Region] {
+        use Region::*;
+        match self {
+            LabialPalatal => &[Bilabial, Palatal],
+            LabialVelar => &[Bilabial, Velar],
+            PostVelar => &[Post, Velar],
+            Bilabial => &[Bilabial],
+            Labiodental => &[Labiodental],
+            Dental => &[Dental],
+            Alveolar => &[Alveolar],
+            Post => &[Post],
+            AlveoloPalatal => &[AlveoloPalatal],
+            Retroflex => &[Retroflex],
+            Palatal => &[Palatal],
+            Velar => &[Velar],
+            Uvular => &[Uvular],
+            Pharyngeal => &[Pharyngeal],
+            Epiglottal => &[Epiglottal],
+            Glottal => &[Glottal]
+        }
+    }
+
+    pub fn is_coarticulated(&self) -> bool {
+        self.places().len() > 1
+    }
+
+    /// Whether the place belongs with the IPA's other symbols
+    /// rather than on the consonant charts
+    pub fn is_other(&self) -> bool {
+        self.is_coarticulated() || //
+            matches!(self, Region::AlveoloPalatal | Region::Epiglottal)
+    }
+}
+
 impl Inner<Voicing> for Region { /*  */ }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -110,15 +178,6 @@ pub enum Voicing {
     Voiced
 }
 
-impl CategoryColor for Voicing {
-    fn as_color(&self) -> egui::Color32 {
-        match self {
-            Voicing::Voiceless => egui::Color32::GOLD,
-            Voicing::Voiced => egui::Color32::RED
-        }
-    }
-}
-
 impl fmt::Display for Voicing {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use Voicing::*;
@@ -191,15 +250,6 @@ pub enum Rounding {
     Rounded
 }
 
-impl CategoryColor for Rounding {
-    fn as_color(&self) -> egui::Color32 {
-        match self {
-            Rounding::Unrounded => egui::Color32::LIGHT_BLUE,
-            Rounding::Rounded => egui::Color32::BLUE
-        }
-    }
-}
-
 impl fmt::Display for Rounding {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use Rounding::*;
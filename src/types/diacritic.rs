@@ -0,0 +1,205 @@
+use std::fmt;
+use std::rc::Rc;
+
+use enum_iterator::Sequence;
+
+use crate::types::PhonemeQuality;
+use crate::types::category::{Articulation, Region, Voicing, Constriction, Place, Rounding};
+
+/// A modifier applied to a phoneme
+/// Each is written with its own mark, and some change the quality the phoneme is charted with
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Sequence)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum Diacritic {
+    Voiceless,
+    Voiced,
+    Breathy,
+    Creaky,
+    Linguolabial,
+    Dental,
+    Apical,
+    Laminal,
+    Advanced,
+    Retracted,
+    Raised,
+    Lowered,
+    VelarizedOrPharyngealized,
+    NoAudibleRelease,
+    Syllabic,
+    MoreRounded,
+    LessRounded,
+    Centralized,
+    MidCentralized,
+    NonSyllabic,
+    Nasalized,
+    AdvancedTongueRoot,
+    RetractedTongueRoot,
+    Rhotic
+}
+
+impl Diacritic {
+    pub fn mark(&self) -> char {
+        use Diacritic::*;
+        match self {
+            Voiceless => '\u{0325}',
+            Voiced => '\u{032C}',
+            Breathy => '\u{0324}',
+            Creaky => '\u{0330}',
+            Linguolabial => '\u{033C}',
+            Dental => '\u{032A}',
+            Apical => '\u{033A}',
+            Laminal => '\u{033B}',
+            Advanced => '\u{031F}',
+            Retracted => '\u{0320}',
+            Raised => '\u{031D}',
+            Lowered => '\u{031E}',
+            VelarizedOrPharyngealized => '\u{0334}',
+            NoAudibleRelease => '\u{031A}',
+            Syllabic => '\u{0329}',
+            MoreRounded => '\u{0339}',
+            LessRounded => '\u{031C}',
+            Centralized => '\u{0308}',
+            MidCentralized => '\u{033D}',
+            NonSyllabic => '\u{032F}',
+            Nasalized => '\u{0303}',
+            AdvancedTongueRoot => '\u{0318}',
+            RetractedTongueRoot => '\u{0319}',
+            Rhotic => '\u{02DE}'
+        }
+    }
+
+    /// Finds the diacritic written with `mark`
+    /// Also accepts the ring above that was once used for voicelessness
+    pub fn from_mark(mark: char) -> Option<Self> {
+        match mark {
+            '\u{030A}' => Some(Diacritic::Voiceless),
+            mark => enum_iterator::all::<Diacritic>().find(|diacritic| diacritic.mark() == mark)
+        }
+    }
+
+    /// True for the diacritics that move a phoneme to a different cell of its chart
+    pub fn modifies_quality(&self) -> bool {
+        use Diacritic::*;
+        matches!(self, Voiceless | Voiced | Dental | MoreRounded | LessRounded | Centralized | MidCentralized)
+    }
+
+    /// Applies the diacritic's effect to the quality of a consonant
+    pub fn modify_consonant(&self, quality: &mut PhonemeQuality<Articulation, Region, Voicing>) {
+        match self {
+            Diacritic::Voiceless => quality.2 = Rc::from(&[Voicing::Voiceless][..]),
+            Diacritic::Voiced => quality.2 = Rc::from(&[Voicing::Voiced][..]),
+            Diacritic::Dental => quality.1 = Rc::from(&[Region::Dental][..]),
+            _ => { /*  */ }
+        }
+    }
+
+    /// Applies the diacritic's effect to the quality of a vowel
+    pub fn modify_vowel(&self, quality: &mut PhonemeQuality<Constriction, Place, Rounding>) {
+        match self {
+            Diacritic::MoreRounded => quality.2 = Rc::from(&[Rounding::Rounded][..]),
+            Diacritic::LessRounded => quality.2 = Rc::from(&[Rounding::Unrounded][..]),
+            Diacritic::Centralized => quality.1 = Rc::from(&[Place::Central][..]),
+            Diacritic::MidCentralized => {
+                quality.0 = Rc::from(&[Constriction::Mid][..]);
+                quality.1 = Rc::from(&[Place::Central][..]);
+            },
+            _ => { /*  */ }
+        }
+    }
+}
+
+impl fmt::Display for Diacritic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Diacritic::*;
+        write!(f, "{}", match self {
+            Voiceless => "Voiceless",
+            Voiced => "Voiced",
+            Breathy => "Breathy",
+            Creaky => "Creaky",
+            Linguolabial => "Linguolabial",
+            Dental => "Dental",
+            Apical => "Apical",
+            Laminal => "Laminal",
+            Advanced => "Advanced",
+            Retracted => "Retracted",
+            Raised => "Raised",
+            Lowered => "Lowered",
+            VelarizedOrPharyngealized => "Velarized or Pharyngealized",
+            NoAudibleRelease => "No Audible Release",
+            Syllabic => "Syllabic",
+            MoreRounded => "More Rounded",
+            LessRounded => "Less Rounded",
+            Centralized => "Centralized",
+            MidCentralized => "Mid-Centralized",
+            NonSyllabic => "Non-Syllabic",
+            Nasalized => "Nasalized",
+            AdvancedTongueRoot => "Advanced Tongue Root",
+            RetractedTongueRoot => "Retracted Tongue Root",
+            Rhotic => "R-Colored"
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Phoneme, Phone};
+    use crate::types::category::{Articulation::*, Region::*, Voicing::*, Constriction::*, Place::*, Rounding::*};
+
+    fn marked(symbol: &str, phone: Phone, diacritics: &[Diacritic]) -> Phoneme {
+        let mut phoneme = Phoneme::new(symbol, phone);
+        phoneme.diacritics.extend(diacritics.iter().copied());
+
+        phoneme
+    }
+
+    #[test]
+    fn marks_are_read_as_their_diacritic() {
+        for diacritic in enum_iterator::all::<Diacritic>() {
+            assert_eq!(Diacritic::from_mark(diacritic.mark()), Some(diacritic));
+        }
+
+        // The ring above is drawn on letters with descenders, but means the same
+        assert_eq!(Diacritic::from_mark('\u{030A}'), Some(Diacritic::Voiceless));
+        assert_eq!(Diacritic::from_mark('a'), None);
+    }
+
+    #[test]
+    fn diacritics_are_written_after_the_symbol() {
+        let phoneme = marked("n", Phone::consonant(), &[Diacritic::Voiceless]);
+
+        assert_eq!(format!("{}", phoneme), "n\u{0325}");
+    }
+
+    #[test]
+    fn some_diacritics_change_a_consonants_quality() {
+        let n = PhonemeQuality::from((Nasal, &[Alveolar, Post][..], Voiced));
+        let t = PhonemeQuality::from((Plosive, &[Dental, Alveolar][..], Voiceless));
+
+        let voiceless = marked("n", Phone::consonant(), &[Diacritic::Voiceless]);
+        assert_eq!(voiceless.consonant_quality(n.clone()), (Nasal, &[Alveolar, Post][..], Voiceless).into());
+
+        let dental = marked("t", Phone::consonant(), &[Diacritic::Dental]);
+        assert_eq!(dental.consonant_quality(t.clone()), (Plosive, Dental, Voiceless).into());
+
+        // Others are only heard, and leave the phoneme in its cell
+        let unreleased = marked("t", Phone::consonant(), &[Diacritic::NoAudibleRelease, Diacritic::Apical]);
+        assert_eq!(unreleased.consonant_quality(t.clone()), t);
+        assert!(!Diacritic::NoAudibleRelease.modifies_quality() && !Diacritic::Apical.modifies_quality());
+    }
+
+    #[test]
+    fn some_diacritics_change_a_vowels_quality() {
+        let e = PhonemeQuality::from((CloseMid, Front, Unrounded));
+
+        let centralized = marked("e", Phone::vowel(), &[Diacritic::MidCentralized]);
+        assert_eq!(centralized.vowel_quality(e.clone()), (Mid, Central, Unrounded).into());
+
+        let rounded = marked("e", Phone::vowel(), &[Diacritic::MoreRounded]);
+        assert_eq!(rounded.vowel_quality(e.clone()), (CloseMid, Front, Rounded).into());
+
+        let nasalized = marked("e", Phone::vowel(), &[Diacritic::Nasalized]);
+        assert_eq!(nasalized.vowel_quality(e.clone()), e);
+    }
+}
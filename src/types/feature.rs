@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use enum_iterator::{Sequence, all};
+
+use crate::types::{PhonemeQuality, Diacritic, Length};
+use crate::types::category::Category;
+use crate::types::category::{Articulation, Region, Voicing, Constriction, Place, Rounding};
+
+/// A distinctive feature, roughly following Hayes' *Introductory Phonology*
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Sequence)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum Feature {
+    Syllabic,
+    Consonantal,
+    Sonorant,
+    Continuant,
+    DelayedRelease,
+    Approximant,
+    Tap,
+    Trill,
+    Nasal,
+    Voice,
+    SpreadGlottis,
+    ConstrictedGlottis,
+    Labial,
+    Round,
+    Labiodental,
+    Coronal,
+    Anterior,
+    Distributed,
+    Strident,
+    Lateral,
+    Dorsal,
+    High,
+    Low,
+    Front,
+    Back,
+    Tense,
+    Long
+}
+
+impl Feature {
+    /// The abbreviation a feature is written with in a selector, such as `+son`
+    pub fn abbreviation(&self) -> &'static str {
+        use Feature::*;
+        match self {
+            Syllabic => "syl",
+            Consonantal => "cons",
+            Sonorant => "son",
+            Continuant => "cont",
+            DelayedRelease => "delrel",
+            Approximant => "approx",
+            Tap => "tap",
+            Trill => "trill",
+            Nasal => "nasal",
+            Voice => "voice",
+            SpreadGlottis => "sg",
+            ConstrictedGlottis => "cg",
+            Labial => "labial",
+            Round => "round",
+            Labiodental => "labiodental",
+            Coronal => "coronal",
+            Anterior => "ant",
+            Distributed => "distr",
+            Strident => "strid",
+            Lateral => "lat",
+            Dorsal => "dorsal",
+            High => "high",
+            Low => "low",
+            Front => "front",
+            Back => "back",
+            Tense => "tense",
+            Long => "long"
+        }
+    }
+
+    /// Accepts either the abbreviation or the full name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        all::<Feature>().find(|feature| {
+            feature.abbreviation().eq_ignore_ascii_case(name) //
+                || format!("{:?}", feature).eq_ignore_ascii_case(name)
+        })
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Feature::*;
+        write!(f, "{}", match self {
+            Syllabic => "Syllabic",
+            Consonantal => "Consonantal",
+            Sonorant => "Sonorant",
+            Continuant => "Continuant",
+            DelayedRelease => "Delayed Release",
+            Approximant => "Approximant",
+            Tap => "Tap",
+            Trill => "Trill",
+            Nasal => "Nasal",
+            Voice => "Voice",
+            SpreadGlottis => "Spread Glottis",
+            ConstrictedGlottis => "Constricted Glottis",
+            Labial => "Labial",
+            Round => "Round",
+            Labiodental => "Labiodental",
+            Coronal => "Coronal",
+            Anterior => "Anterior",
+            Distributed => "Distributed",
+            Strident => "Strident",
+            Lateral => "Lateral",
+            Dorsal => "Dorsal",
+            High => "High",
+            Low => "Low",
+            Front => "Front",
+            Back => "Back",
+            Tense => "Tense",
+            Long => "Long"
+        })
+    }
+}
+
+/// A bundle of feature values
+/// Features that aren't listed don't apply, such as [anterior] for a non-coronal
+/// The same bundle selects every phoneme that shares all of its values
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Features(pub BTreeMap<Feature, bool>);
+
+impl Features {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, feature: Feature) -> Option<bool> {
+        self.0.get(&feature).copied()
+    }
+
+    pub fn set(&mut self, feature: Feature, value: bool) {
+        self.0.insert(feature, value);
+    }
+
+    pub fn unset(&mut self, feature: Feature) {
+        self.0.remove(&feature);
+    }
+
+    /// True if every value in `selector` is shared by the bundle
+    pub fn meets(&self, selector: &Features) -> bool {
+        selector.0
+            .iter()
+            .all(|(feature, value)| self.get(*feature) == Some(*value))
+    }
+
+    /// Keeps only the values that both bundles agree on
+    pub fn intersection(&self, other: &Features) -> Features {
+        Features(self.0
+            .iter()
+            .filter(|(feature, value)| other.get(**feature) == Some(**value))
+            .map(|(feature, value)| (*feature, *value))
+            .collect())
+    }
+
+    /// Reads a value written like `+son`, `-nasal` or `−nasal`
+    pub fn parse_value(text: &str) -> Option<(Feature, bool)> {
+        let mut chars = text.chars();
+
+        let value = match chars.next()? {
+            '+' => true,
+            '-' | '−' => false,
+            _ => return None
+        };
+
+        Feature::from_name(chars.as_str()).map(|feature| (feature, value))
+    }
+
+    /// Combines the bundles of every cell a quality spans,
+    /// keeping the values that all of them agree on
+    fn agreed<A, B, C, F>(quality: PhonemeQuality<A, B, C>, cell: F) -> Self
+        where A: Category, B: Category, C: Category, F: Fn(A, B, C) -> Features {
+
+        quality
+            .into_iter()
+            .map(|(a, b, c)| cell(a, b, c))
+            .reduce(|agreed, features| agreed.intersection(&features))
+            .unwrap_or_default()
+    }
+
+    pub fn consonant(quality: PhonemeQuality<Articulation, Region, Voicing>, affricated: bool) -> Self {
+        Self::agreed(quality, |articulation, region, voicing| {
+            let mut features = consonant_cell(articulation, region, voicing);
+
+            if affricated {
+                features.set(Feature::DelayedRelease, true);
+            }
+
+            features
+        })
+    }
+
+    pub fn vowel(quality: PhonemeQuality<Constriction, Place, Rounding>) -> Self {
+        Self::agreed(quality, vowel_cell)
+    }
+
+    /// Applies the effects of a phoneme's diacritics and length
+    pub fn modify(&mut self, diacritics: impl Iterator<Item = Diacritic>, length: Length) {
+        use Feature::*;
+
+        for diacritic in diacritics {
+            match diacritic {
+                Diacritic::Voiceless => self.set(Voice, false),
+                Diacritic::Voiced => self.set(Voice, true),
+                Diacritic::Breathy => self.set(SpreadGlottis, true),
+                Diacritic::Creaky => self.set(ConstrictedGlottis, true),
+                Diacritic::Linguolabial => self.set(Coronal, true),
+                Diacritic::Apical => self.set(Distributed, false),
+                Diacritic::Laminal => self.set(Distributed, true),
+                Diacritic::VelarizedOrPharyngealized => {
+                    self.set(Dorsal, true);
+                    self.set(Back, true);
+                },
+                Diacritic::Syllabic => self.set(Syllabic, true),
+                Diacritic::NonSyllabic => self.set(Syllabic, false),
+                Diacritic::MoreRounded => {
+                    self.set(Labial, true);
+                    self.set(Round, true);
+                },
+                Diacritic::LessRounded => self.set(Round, false),
+                Diacritic::Nasalized => self.set(Nasal, true),
+                Diacritic::AdvancedTongueRoot => self.set(Tense, true),
+                Diacritic::RetractedTongueRoot => self.set(Tense, false),
+                _ => { /*  */ }
+            }
+        }
+
+        self.set(Long, length == Length::Long);
+    }
+}
+
+impl fmt::Display for Features {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = self.0
+            .iter()
+            .map(|(feature, value)| format!("{}{}", if *value { '+' } else { '-' }, feature.abbreviation()))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", values.join(" "))
+    }
+}
+
+fn consonant_cell(articulation: Articulation, region: Region, voicing: Voicing) -> Features {
+    use Articulation::*;
+    use Region::*;
+
+    let mut features = Features::default();
+
+    let sonorant = matches!(articulation, Nasal | Trill | Flap | LatFlap | Approximant | LatApproximant);
+    let fricated = matches!(articulation, Fricative | LatFricative | EjFricative);
+    let approximant = matches!(articulation, Trill | Flap | LatFlap | Approximant | LatApproximant);
+
+    // Glides and laryngeals are the only consonants that aren't [+consonantal]
+    let glide = articulation == Approximant //
+        && matches!(region, Palatal | Velar | LabialPalatal | LabialVelar);
+
+    features.set(Feature::Syllabic, false);
+    features.set(Feature::Consonantal, !glide && region != Glottal);
+    features.set(Feature::Sonorant, sonorant);
+    features.set(Feature::Continuant, fricated || approximant);
+    features.set(Feature::DelayedRelease, fricated);
+    features.set(Feature::Approximant, approximant);
+    features.set(Feature::Tap, matches!(articulation, Flap | LatFlap));
+    features.set(Feature::Trill, articulation == Trill);
+    features.set(Feature::Nasal, articulation == Nasal);
+    features.set(Feature::Voice, voicing == Voicing::Voiced);
+    features.set(Feature::SpreadGlottis, region == Glottal && articulation == Fricative && voicing == Voicing::Voiceless);
+    features.set(Feature::ConstrictedGlottis, matches!(articulation, Implosive | Ejective | EjFricative) //
+        || (region == Glottal && articulation == Plosive));
+    features.set(Feature::Lateral, matches!(articulation, LatFlap | LatFricative | LatApproximant | LatClick));
+
+    let labial = matches!(region, Bilabial | Region::Labiodental | LabialPalatal | LabialVelar);
+    features.set(Feature::Labial, labial);
+    if labial {
+        features.set(Feature::Round, glide);
+        features.set(Feature::Labiodental, region == Region::Labiodental);
+    }
+
+    let coronal = matches!(region, Dental | Alveolar | Post | AlveoloPalatal | Retroflex | PostVelar);
+    features.set(Feature::Coronal, coronal);
+    if coronal {
+        features.set(Feature::Anterior, matches!(region, Dental | Alveolar));
+        features.set(Feature::Distributed, matches!(region, Dental | Post | AlveoloPalatal | PostVelar));
+        features.set(Feature::Strident, (fricated || articulation == Click) //
+            && matches!(region, Alveolar | Post | AlveoloPalatal));
+    }
+
+    let dorsal = matches!(region, Palatal | Velar | Uvular | LabialPalatal | LabialVelar | PostVelar);
+    features.set(Feature::Dorsal, dorsal);
+    if dorsal {
+        features.set(Feature::High, region != Uvular);
+        features.set(Feature::Low, false);
+        features.set(Feature::Front, matches!(region, Palatal | LabialPalatal));
+        features.set(Feature::Back, matches!(region, Velar | Uvular | LabialVelar | PostVelar));
+    }
+
+    features.set(Feature::Long, false);
+
+    features
+}
+
+fn vowel_cell(constriction: Constriction, place: Place, rounding: Rounding) -> Features {
+    use Feature::*;
+    use Constriction::*;
+
+    let mut features = Features::default();
+
+    features.set(Syllabic, true);
+    features.set(Consonantal, false);
+    features.set(Sonorant, true);
+    features.set(Continuant, true);
+    features.set(DelayedRelease, false);
+    features.set(Approximant, true);
+    features.set(Tap, false);
+    features.set(Trill, false);
+    features.set(Nasal, false);
+    features.set(Voice, true);
+    features.set(SpreadGlottis, false);
+    features.set(ConstrictedGlottis, false);
+    features.set(Lateral, false);
+
+    features.set(Labial, rounding == Rounding::Rounded);
+    features.set(Round, rounding == Rounding::Rounded);
+    features.set(Coronal, false);
+
+    features.set(Dorsal, true);
+    features.set(High, matches!(constriction, Close | CloseNear));
+    features.set(Low, matches!(constriction, OpenNear | Open));
+    features.set(Feature::Front, place == Place::Front);
+    features.set(Feature::Back, place == Place::Back);
+
+    // Tenseness only contrasts among the non-low vowels
+    match constriction {
+        Close | CloseMid => features.set(Tense, true),
+        CloseNear | OpenMid => features.set(Tense, false),
+        _ => { /*  */ }
+    }
+
+    features.set(Long, false);
+
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use crate::types::{Lineage, parse_word};
+
+    /// Every phoneme charted in the IPA that meets `selector`, sorted by symbol
+    fn selected(lineage: &Lineage<'_>, selector: &[(Feature, bool)]) -> Vec<String> {
+        let selector = Features(selector.iter().copied().collect());
+
+        let mut selected = lineage
+            .candidates()
+            .filter(|(id, _)| lineage.features(*id).map_or(false, |features| features.meets(&selector)))
+            .map(|(_, symbol)| symbol)
+            .collect::<Vec<_>>();
+
+        selected.sort();
+        selected
+    }
+
+    #[test]
+    fn values_are_read_by_name_or_abbreviation() {
+        assert_eq!(Features::parse_value("+son"), Some((Feature::Sonorant, true)));
+        assert_eq!(Features::parse_value("-nasal"), Some((Feature::Nasal, false)));
+        assert_eq!(Features::parse_value("−Nasal"), Some((Feature::Nasal, false)));
+        assert_eq!(Features::parse_value("+DelayedRelease"), Some((Feature::DelayedRelease, true)));
+
+        assert_eq!(Features::parse_value("son"), None);
+        assert_eq!(Features::parse_value("+sonorous"), None);
+    }
+
+    #[test]
+    fn selectors_pick_every_phoneme_with_their_values() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        assert_eq!(selected(&lineage, &[(Feature::Nasal, true)]), 
+            vec!["m", "n", "ŋ", "ŋ\u{0361}m", "ɱ", "ɲ", "ɳ", "ɴ"]);
+
+        assert_eq!(selected(&lineage, &[(Feature::Syllabic, true), (Feature::High, true), (Feature::Round, true)]), 
+            vec!["u", "y", "ʉ", "ʊ", "ʏ"]);
+
+        assert_eq!(selected(&lineage, &[(Feature::Lateral, true), (Feature::Continuant, false)]), 
+            vec!["ǁ"]);
+    }
+
+    #[test]
+    fn diacritics_change_the_values_of_a_phoneme() {
+        let mut state = State::default();
+
+        let ids = parse_word("na", state.lineage(state.root).candidates()).unwrap();
+
+        let _ = state.phonemes[ids[0]].diacritics.insert(Diacritic::Voiceless);
+        let _ = state.phonemes[ids[1]].diacritics.insert(Diacritic::Nasalized);
+
+        let lineage = state.lineage(state.root);
+        let (n, a) = (lineage.features(ids[0]).unwrap(), lineage.features(ids[1]).unwrap());
+
+        assert_eq!((n.get(Feature::Voice), n.get(Feature::Nasal)), (Some(false), Some(true)));
+        assert_eq!((a.get(Feature::Nasal), a.get(Feature::Syllabic)), (Some(true), Some(true)));
+
+        let nasal = selected(&lineage, &[(Feature::Nasal, true), (Feature::Voice, false)]);
+        assert_eq!(nasal, vec!["n\u{0325}"]);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use slotmap::DefaultKey;
+
+use crate::types::{Phonotactics, SoundChangeContext, Lineage, Random, Word};
+
+/// Settings for generating words from a language's inventory and syllable templates
+#[derive(Clone)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Generator {
+    pub seed: u64,
+    pub syllables: (usize, usize),
+    pub weights: HashMap<DefaultKey, f32>
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            syllables: (1, 3),
+            weights: HashMap::new()
+        }
+    }
+}
+
+/// The number of attempts made at each word before giving up on it
+const ATTEMPTS: usize = 64;
+
+impl Generator {
+    /// Phonemes are equally likely unless given a weight
+    pub fn weight(&self, id: DefaultKey) -> f32 {
+        self.weights.get(&id).copied().unwrap_or(1.)
+    }
+
+    fn fill(
+        &self,
+        random: &mut Random,
+        lineage: &Lineage<'_>,
+        phonemes: &[DefaultKey],
+        context: &SoundChangeContext,
+        word: &mut Word) -> Option<()> {
+
+        match context {
+            SoundChangeContext::Multiple(sequence) => {
+                for context in sequence.iter() {
+                    self.fill(random, lineage, phonemes, context, word)?;
+                }
+            },
+            SoundChangeContext::Boundary | SoundChangeContext::Unrestricted => { /*  */ },
+            context => {
+                let candidates = phonemes
+                    .iter()
+                    .filter(|id| context.matches_after(lineage, &[**id], 0).is_some())
+                    .map(|id| (*id, self.weight(*id)))
+                    .collect::<Vec<_>>();
+
+                word.push(*random.weighted(&candidates)?);
+            }
+        }
+
+        Some(())
+    }
+
+    fn attempt(
+        &self,
+        random: &mut Random,
+        lineage: &Lineage<'_>,
+        phonotactics: &Phonotactics,
+        phonemes: &[DefaultKey]) -> Option<Word> {
+
+        let (min, max) = self.syllables;
+        let (min, max) = (min.max(1), max.max(min.max(1)));
+
+        let count = min + random.below(max - min + 1);
+
+        let mut word = Word::new();
+        for _ in 0..count {
+            let template = &phonotactics.templates[random.below(phonotactics.templates.len())];
+
+            for slot in template.0.iter() {
+                if slot.optional && random.next_f32() < 0.5 { continue; }
+
+                let class = phonotactics.class(slot.class)?;
+
+                self.fill(random, lineage, phonemes, class, &mut word)?;
+            }
+        }
+
+        Some(word).filter(|word| {
+            !word.is_empty() && phonotactics.validate(lineage, word).is_empty()
+        })
+    }
+
+    /// Generates up to `count` words that satisfy the language's phonotactics
+    /// `phonemes` must be given in a stable order for the seed to reproduce a batch
+    /// Returns nothing if the language has no syllable templates
+    pub fn generate(
+        &self, 
+        lineage: &Lineage<'_>,
+        phonotactics: &Phonotactics,
+        phonemes: &[DefaultKey],
+        count: usize) -> Vec<Word> {
+
+        if phonotactics.templates.is_empty() { return Vec::new(); }
+
+        let mut random = Random::new(self.seed);
+
+        (0..count)
+            .filter_map(|_| (0..ATTEMPTS)
+                .find_map(|_| self.attempt(&mut random, lineage, phonotactics, phonemes)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use crate::types::{Language, parse_word};
+
+    /// Copies the IPA's phonemes for each symbol of `text` into the dialect at `id`, as the inventory pane does
+    fn chart(state: &mut State, node: petgraph::stable_graph::NodeIndex<u32>, text: &str) {
+        let ipa = parse_word(text, state.ipa.phonemes().map(|id| (id, format!("{}", state.phonemes[id])))).unwrap();
+
+        for source in ipa {
+            let phoneme = state.phonemes[source].clone();
+            let id = state.phonemes.insert(phoneme);
+            state.phonemes[id].set_id(id);
+
+            let dialect = &mut state.dialects[state.language_tree[node]];
+            match state.ipa.consonants.get_quality(source) {
+                Some(quality) => dialect.consonants.add_phoneme(id, quality),
+                None => dialect.vowels.add_phoneme(id, state.ipa.vowels.get_quality(source).unwrap())
+            }
+        }
+    }
+
+    #[test]
+    fn a_seed_always_generates_the_same_words() {
+        let mut state = State::default();
+        let root = state.root;
+        chart(&mut state, root, "ptkaiu");
+
+        let language = &mut state.dialects[state.inventory];
+
+        let template = language.phonotactics.parse_template("CV(C)").unwrap();
+        language.phonotactics.templates.push(template);
+        language.generator.seed = 7;
+
+        let generate = |state: &State| state.dialects[state.inventory]
+            .generate_words(&state.inventory_lineage(root), 16);
+
+        let words = generate(&state);
+
+        assert_eq!(words.len(), 16);
+        assert_eq!(words, generate(&state));
+
+        let lineage = state.inventory_lineage(root);
+        let phonotactics = &state.dialects[state.inventory].phonotactics;
+        assert!(words.iter().all(|word| phonotactics.validate(&lineage, word).is_empty()));
+
+        state.dialects[state.inventory].generator.seed = 8;
+
+        assert_ne!(words, generate(&state));
+    }
+
+    #[test]
+    fn dialects_generate_words_with_the_phonemes_they_inherit() {
+        let mut state = State::default();
+        let root = state.root;
+        chart(&mut state, root, "pa");
+
+        let dialect = state.dialects.insert(Language::default());
+        let child = state.language_tree.add_node(dialect);
+        state.language_tree.add_edge(root, child, ());
+
+        let template = Phonotactics::default().parse_template("CV").unwrap();
+        state.dialects[dialect].phonotactics.templates.push(template);
+        state.dialects[dialect].generator.syllables = (1, 1);
+
+        let lineage = state.inventory_lineage(child);
+
+        let words = state.dialects[dialect]
+            .generate_words(&lineage, 4)
+            .iter()
+            .map(|word| crate::types::word_to_string(&state.phonemes, word))
+            .collect::<Vec<_>>();
+
+        assert_eq!(words, vec!["pa"; 4]);
+    }
+}
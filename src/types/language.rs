@@ -1,7 +1,10 @@
-
+use std::collections::HashMap;
 use std::rc;
 
-use crate::types::{Alphabet, SoundChange};
+use slotmap::{DefaultKey, SlotMap};
+
+use crate::types::{Alphabet, SoundChange, Lineage, Word, Lexicon, Phonotactics, Generator};
+use crate::types::{Prosody, Tone, EntryKey, Phoneme};
 
 use crate::types::category::{
     Articulation, 
@@ -12,12 +15,55 @@ use crate::types::category::{
     Rounding
 };
 
+/// A named period of a dialect's history, beginning with the sound change at `start`
+#[derive(Clone, Default)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Stage {
+    pub name: String,
+    pub start: usize
+}
+
+/// An entry of a dialect's chronology, which lists stages alongside the sound changes they begin with
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Period {
+    Stage(usize),
+    SoundChange(usize)
+}
+
+/// A word after a single sound change was applied to it
+pub struct Step {
+    pub word: Word,
+    pub prosody: Prosody,
+    pub fired: bool
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct Language {
     pub name: rc::Rc<str>,
     pub consonants: Alphabet<Articulation, Region, Voicing>,
     pub vowels: Alphabet<Constriction, Place, Rounding>,
-    pub sound_changes: Vec<SoundChange>
+    pub sound_changes: Vec<SoundChange>,
+
+    /// Ordered by their starts, sound changes before the first stage belong to no stage
+    #[serde(default)]
+    pub stages: Vec<Stage>,
+
+    /// Diphthongs carry their qualities in their `Phone`, 
+    /// so they don't need an alphabet of their own
+    #[serde(default)]
+    pub diphthongs: Vec<DefaultKey>,
+
+    #[serde(default)]
+    pub tones: Vec<Tone>,
+
+    #[serde(default)]
+    pub lexicon: Lexicon,
+
+    #[serde(default)]
+    pub phonotactics: Phonotactics,
+
+    #[serde(default)]
+    pub generator: Generator
 }
 
 impl Default for Language {
@@ -26,7 +72,276 @@ impl Default for Language {
             name: rc::Rc::from("Untitled"),
             consonants: Alphabet::new(), 
             vowels: Alphabet::new(),
-            sound_changes: Vec::new()
+            sound_changes: Vec::new(),
+            stages: Vec::new(),
+            diphthongs: Vec::new(),
+            tones: Vec::new(),
+            lexicon: Lexicon::with_key(),
+            phonotactics: Phonotactics::default(),
+            generator: Generator::default()
+        }
+    }
+}
+
+impl Language {
+    pub fn phonemes(&self) -> impl Iterator<Item = DefaultKey> + '_ {
+        self.consonants.phonemes()
+            .chain(self.vowels.phonemes())
+            .chain(self.diphthongs.iter().copied())
+    }
+
+    /// Produces this dialect's reflex of a word from its parent language
+    /// Sound changes are applied in the order they were added
+    /// `entry` is the entry of the root language's lexicon that the word descends from, if any
+    pub fn apply_sound_changes(
+        &self, 
+        lineage: &Lineage<'_>, 
+        word: &[DefaultKey], 
+        prosody: &Prosody,
+        entry: Option<EntryKey>) -> (Word, Prosody) {
+
+        let (mut word, mut prosody) = (word.to_vec(), prosody.clone());
+
+        for sound_change in self.sound_changes.iter() {
+            sound_change.apply(lineage, &mut word, &mut prosody, entry);
+        }
+
+        (word, prosody)
+    }
+
+    /// Applies the sound changes one at a time, keeping the word as it was after each
+    pub fn trace_sound_changes(
+        &self, 
+        lineage: &Lineage<'_>, 
+        word: &[DefaultKey], 
+        prosody: &Prosody,
+        entry: Option<EntryKey>) -> Vec<Step> {
+
+        let (mut word, mut prosody) = (word.to_vec(), prosody.clone());
+
+        self.sound_changes
+            .iter()
+            .map(|sound_change| {
+                let fired = sound_change.apply(lineage, &mut word, &mut prosody, entry);
+
+                Step { word: word.clone(), prosody: prosody.clone(), fired }
+            })
+            .collect()
+    }
+
+    /// Every stage and sound change in order, with each stage placed before the sound changes it begins with
+    pub fn chronology(&self) -> Vec<Period> {
+        let mut chronology = Vec::new();
+
+        let mut stages = self.stages.iter().enumerate().peekable();
+        for idx in 0..=self.sound_changes.len() {
+            while let Some((stage, _)) = stages.next_if(|(_, stage)| stage.start <= idx) {
+                chronology.push(Period::Stage(stage));
+            }
+
+            if idx < self.sound_changes.len() {
+                chronology.push(Period::SoundChange(idx));
+            }
+        }
+
+        chronology
+    }
+
+    /// Moves the sound change at `idx` to just before the entry at `position` of the chronology,
+    /// or to the very end if `position` is past the last entry
+    /// The sound change joins whichever stage it lands in
+    pub fn move_sound_change(&mut self, idx: usize, position: usize) {
+        let mut chronology = self.chronology();
+
+        let from = chronology
+            .iter()
+            .position(|period| *period == Period::SoundChange(idx))
+            .unwrap();
+
+        chronology.remove(from);
+
+        // Entries after the sound change moved up when it was removed
+        let position = if position > from { position - 1 } else { position };
+
+        chronology.insert(position.min(chronology.len()), Period::SoundChange(idx));
+
+        let mut sound_changes = std::mem::take(&mut self.sound_changes)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+
+        for period in chronology {
+            match period {
+                Period::Stage(stage) => self.stages[stage].start = self.sound_changes.len(),
+                Period::SoundChange(idx) => self.sound_changes.push(sound_changes[idx].take().unwrap())
+            }
         }
     }
-}
\ No newline at end of file
+
+    /// Shifts the stages after a sound change that was removed, so that they still begin with the same ones
+    pub fn forget_sound_change(&mut self, idx: usize) {
+        for stage in self.stages.iter_mut().filter(|stage| stage.start > idx) {
+            stage.start -= 1;
+        }
+    }
+
+    /// Every phoneme that the language refers to without charting it itself, 
+    /// such as the sources of its sound changes and the phonemes its words are written with
+    pub fn references(&self) -> Vec<DefaultKey> {
+        self.sound_changes
+            .iter()
+            .flat_map(|sound_change| sound_change.change
+                .phonemes()
+                .into_iter()
+                .chain(sound_change.context.0.phonemes())
+                .chain(sound_change.context.1.phonemes()))
+            .chain(self.lexicon.values().flat_map(|entry| entry.form.iter().copied()))
+            .chain(self.phonotactics.banned.iter().flatten().copied())
+            .chain(self.generator.weights.keys().copied())
+            .collect()
+    }
+
+    /// Replaces every phoneme in `references` with the one `map` gives for it
+    pub fn remap(&mut self, map: impl Fn(DefaultKey) -> DefaultKey) {
+        for sound_change in self.sound_changes.iter_mut() {
+            sound_change.change.remap(&map);
+            sound_change.context.0.remap(&map);
+            sound_change.context.1.remap(&map);
+        }
+
+        let remap_word = |word: &mut Word| word.iter_mut().for_each(|id| *id = map(*id));
+
+        self.lexicon.values_mut().for_each(|entry| remap_word(&mut entry.form));
+        self.phonotactics.banned.iter_mut().for_each(remap_word);
+
+        self.generator.weights = std::mem::take(&mut self.generator.weights)
+            .into_iter()
+            .map(|(id, weight)| (map(id), weight))
+            .collect();
+    }
+
+    /// A deep copy of the language, whose own phonemes are copied with fresh keys
+    /// Phonemes it inherited are still shared with its ancestors
+    pub fn duplicate(&self, phonemes: &mut SlotMap<DefaultKey, Phoneme>) -> Self {
+        let copies = self.phonemes()
+            .map(|id| {
+                let copy = phonemes.insert(phonemes[id].clone());
+
+                phonemes[copy].set_id(copy);
+                (id, copy)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let remap = |id: DefaultKey| copies.get(&id).copied().unwrap_or(id);
+
+        let mut consonants = Alphabet::new();
+        for (id, quality) in self.consonants.phoneme_qualities() {
+            consonants.add_phoneme(remap(id), quality);
+        }
+
+        let mut vowels = Alphabet::new();
+        for (id, quality) in self.vowels.phoneme_qualities() {
+            vowels.add_phoneme(remap(id), quality);
+        }
+
+        let mut duplicate = Self {
+            name: rc::Rc::from(format!("{} (Copy)", self.name)),
+            consonants,
+            vowels,
+            sound_changes: self.sound_changes.clone(),
+            stages: self.stages.clone(),
+            diphthongs: self.diphthongs.iter().map(|id| remap(*id)).collect(),
+            tones: self.tones.clone(),
+            lexicon: self.lexicon.clone(),
+            phonotactics: self.phonotactics.clone(),
+            generator: self.generator.clone()
+        };
+
+        duplicate.remap(remap);
+        duplicate
+    }
+
+    /// Generates words with the language's own settings from every sound in `lineage`,
+    /// which should be the language's inventory lineage so that the IPA's sounds are left out
+    /// Phonemes are ordered by their symbols, so that a seed always produces the same batch
+    pub fn generate_words(&self, lineage: &Lineage<'_>, count: usize) -> Vec<Word> {
+        let phonemes = lineage
+            .sounds()
+            .into_iter()
+            .map(|(_, id)| id)
+            .collect::<Vec<_>>();
+
+        self.generator.generate(lineage, &self.phonotactics, &phonemes, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use crate::types::{parse_rules, parse_word, word_to_string};
+
+    fn language(state: &mut State, text: &str) {
+        let (rules, stages) = parse_rules(text, &state.lineage(state.root), &state.lineage(state.root)).unwrap();
+        state.replace_sound_changes(state.root, rules, stages);
+    }
+
+    #[test]
+    fn stages_begin_with_the_sound_changes_after_them() {
+        let mut state = State::default();
+        language(&mut state, "p > f\nMiddle:\nt > s\nLate:\nk > x");
+
+        let dialect = &mut state.dialects[state.inventory];
+
+        assert_eq!(dialect.chronology(), [
+            Period::SoundChange(0), 
+            Period::Stage(0), 
+            Period::SoundChange(1), 
+            Period::Stage(1), 
+            Period::SoundChange(2)
+        ]);
+
+        // Moving p > f to just before the last stage makes it the second sound change of the middle one
+        dialect.move_sound_change(0, 3);
+        assert_eq!(dialect.chronology(), [
+            Period::Stage(0), 
+            Period::SoundChange(0), 
+            Period::SoundChange(1), 
+            Period::Stage(1), 
+            Period::SoundChange(2)
+        ]);
+
+        assert_eq!(dialect.stages.iter().map(|stage| stage.start).collect::<Vec<_>>(), [0, 2]);
+
+        // Moving it past the end leaves it in the last stage
+        dialect.move_sound_change(1, 5);
+        assert_eq!(dialect.stages.iter().map(|stage| stage.start).collect::<Vec<_>>(), [0, 1]);
+
+        dialect.sound_changes.remove(0);
+        dialect.forget_sound_change(0);
+        assert_eq!(dialect.stages.iter().map(|stage| stage.start).collect::<Vec<_>>(), [0, 0]);
+    }
+
+    #[test]
+    fn traces_keep_the_word_after_each_sound_change() {
+        let mut state = State::default();
+        language(&mut state, "p > f\nt > s / _#\nk > x");
+
+        let word = parse_word("pat", state.lineage(state.root).candidates()).unwrap();
+        let steps = state.trace(&word, &Prosody::default(), state.root, None);
+
+        let steps = steps
+            .iter()
+            .map(|step| (word_to_string(&state.phonemes, &step.word), step.fired))
+            .collect::<Vec<_>>();
+
+        assert_eq!(steps, [
+            (String::from("fat"), true), 
+            (String::from("fas"), true), 
+            (String::from("fas"), false)
+        ]);
+
+        let (word, _) = state.derive(&word, &Prosody::default(), state.root, None);
+        assert_eq!(word_to_string(&state.phonemes, &word), "fas");
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+use enum_iterator::Sequence;
+use slotmap::SlotMap;
+
+use crate::types::{Word, Prosody};
+
+slotmap::new_key_type! { pub struct EntryKey; }
+
+pub type Lexicon = SlotMap<EntryKey, Entry>;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[derive(Sequence)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum PartOfSpeech {
+    #[default]
+    Noun,
+    Verb,
+    Adjective,
+    Adverb,
+    Pronoun,
+    Preposition,
+    Conjunction,
+    Interjection,
+    Particle,
+    Other
+}
+
+impl fmt::Display for PartOfSpeech {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use PartOfSpeech::*;
+        write!(f, "{}", match self {
+            Noun => "Noun",
+            Verb => "Verb",
+            Adjective => "Adjective",
+            Adverb => "Adverb",
+            Pronoun => "Pronoun",
+            Preposition => "Preposition",
+            Conjunction => "Conjunction",
+            Interjection => "Interjection",
+            Particle => "Particle",
+            Other => "Other"
+        })
+    }
+}
+
+#[derive(Clone, Default)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Entry {
+    pub form: Word,
+    /// Stress and tone of the form's syllables
+    #[serde(default)]
+    pub prosody: Prosody,
+    pub gloss: String,
+    pub part_of_speech: PartOfSpeech,
+    pub notes: String
+}
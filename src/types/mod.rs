@@ -4,8 +4,28 @@ mod phoneme;
 mod alphabet;
 mod language;
 mod sound_change;
+mod word;
+mod rule;
+mod lexicon;
+mod phonotactics;
+mod random;
+mod generator;
+mod romanization;
+mod prosody;
+mod diacritic;
+mod feature;
 
 pub use phoneme::*;
 pub use alphabet::*;
 pub use language::*;
-pub use sound_change::{SoundChange, SoundChangeContext};
\ No newline at end of file
+pub use sound_change::{SoundChange, SoundChangeContext, Change, Application, Lineage, resolve_shift};
+pub use word::*;
+pub use rule::*;
+pub use lexicon::*;
+pub use phonotactics::*;
+pub use random::*;
+pub use generator::*;
+pub use romanization::*;
+pub use prosody::*;
+pub use diacritic::*;
+pub use feature::*;
@@ -1,17 +1,13 @@
-use std::{mem, fmt, vec};
+use std::{fmt, vec};
+use std::collections::BTreeSet;
 use std::rc::Rc;
 use std::borrow::Cow;
 
 use enum_iterator::{Sequence, all};
 
 use crate::types::category::Category;
-use crate::app::FONT_ID;
-
-const CONSONANT_: &Phone = &Phone::consonant();
-const VOWEL_: &Phone = &Phone::vowel();
-
-pub const CONSONANT: mem::Discriminant<Phone> = mem::discriminant(CONSONANT_);
-pub const VOWEL: mem::Discriminant<Phone> = mem::discriminant(VOWEL_);
+use crate::types::category::{Articulation, Region, Voicing, Constriction, Place, Rounding};
+use crate::types::{ContextualSpelling, Length, Diacritic, Features};
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -20,7 +16,12 @@ pub enum Phone {
         affricated: Option<Rc<str>>,
         regionalized: Option<Rc<str>>
     },
-    Vowel
+    Vowel,
+    /// A vowel that glides from one quality to another within a single segment
+    Diphthong {
+        start: (Constriction, Place, Rounding),
+        end: (Constriction, Place, Rounding)
+    }
 }
 
 impl Phone {
@@ -32,6 +33,32 @@ impl Phone {
         Self::Vowel
     }
 
+    pub fn is_consonant(&self) -> bool {
+        matches!(self, Self::Consonant { .. })
+    }
+
+    pub fn is_vowel(&self) -> bool {
+        matches!(self, Self::Vowel)
+    }
+
+    pub const fn diphthong(
+        start: (Constriction, Place, Rounding), 
+        end: (Constriction, Place, Rounding)) -> Self {
+        Self::Diphthong { start, end }
+    }
+
+    pub fn is_diphthong(&self) -> bool {
+        matches!(self, Self::Diphthong { .. })
+    }
+
+    /// The qualities a diphthong starts and ends on
+    pub fn glide(&self) -> Option<[PhonemeQuality<Constriction, Place, Rounding>; 2]> {
+        match self {
+            Self::Diphthong { start, end } => Some([(*start).into(), (*end).into()]),
+            _ => None
+        }
+    }
+
     pub fn regionalize<'a, C: Into<Cow<'a, str>>>(&mut self, symbol: C) {
         if let Self::Consonant { ref mut regionalized, .. } = self {
             let symbol: Cow<'_, str> = symbol.into();
@@ -58,9 +85,17 @@ impl Phone {
 #[derive(Clone, PartialEq, Eq, Hash)]
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct Phoneme {
+    /// The base symbol, without any of the phoneme's diacritics
     pub symbol: String,
+    #[serde(default)]
+    pub diacritics: BTreeSet<Diacritic>,
     pub grapheme: String,
+    /// Checked in order before falling back to `grapheme`
+    #[serde(default)]
+    pub spellings: Vec<ContextualSpelling>,
     pub phone: Phone,
+    #[serde(default)]
+    pub length: Length,
     id: slotmap::DefaultKey,
     id_state: bool
 }
@@ -71,8 +106,11 @@ impl Phoneme {
 
         Self {
             symbol: symbol.to_string(),
+            diacritics: BTreeSet::new(),
             grapheme: String::new(),
+            spellings: Vec::new(),
             phone,
+            length: Length::Short,
             id: slotmap::DefaultKey::default(),
             id_state: false
         }
@@ -94,30 +132,74 @@ impl fmt::Display for Phoneme {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.phone {
             Phone::Consonant { affricated, regionalized } => {
-                write!(f, "{}{}{}", 
+                write!(f, "{}{}{}{}{}", 
                     self.symbol,
+                    self.marks(),
                     regionalized.as_ref().unwrap_or(&Rc::from("")),
                     affricated.as_ref().map_or(String::from(""), |affricate| {
                         format!("\u{0361}{}", affricate)
-                    })
+                    }),
+                    self.length.mark()
                 )
             },
-            Phone::Vowel => write!(f, "{}", self.symbol),
+            Phone::Vowel | Phone::Diphthong { .. } => 
+                write!(f, "{}{}{}", self.symbol, self.marks(), self.length.mark()),
         }
     }
 }
 
 impl Phoneme {
-    pub fn add_diacritic<'a, C: Into<Cow<'a, str>>>(&mut self, diacritic: C) {
-        let diacritic: Cow<'_, str> = diacritic.into();
+    fn marks(&self) -> String {
+        self.diacritics.iter().map(Diacritic::mark).collect()
+    }
 
-        self.symbol.push_str(diacritic.as_ref());
+    /// The quality of a consonant once its diacritics have been applied 
+    /// to the quality it's charted with
+    pub fn consonant_quality(
+        &self, 
+        mut quality: PhonemeQuality<Articulation, Region, Voicing>) -> PhonemeQuality<Articulation, Region, Voicing> {
+
+        for diacritic in self.diacritics.iter() {
+            diacritic.modify_consonant(&mut quality);
+        }
+
+        quality
     }
-}
 
-impl Into<egui::RichText> for Phoneme {
-    fn into(self) -> egui::RichText {
-        egui::RichText::from(format!("{}", self)).font(FONT_ID.to_owned())
+    /// The quality of a vowel once its diacritics have been applied 
+    /// to the quality it's charted with
+    pub fn vowel_quality(
+        &self, 
+        mut quality: PhonemeQuality<Constriction, Place, Rounding>) -> PhonemeQuality<Constriction, Place, Rounding> {
+
+        for diacritic in self.diacritics.iter() {
+            diacritic.modify_vowel(&mut quality);
+        }
+
+        quality
+    }
+
+    /// The distinctive features of a consonant charted at `quality`
+    pub fn consonant_features(&self, quality: PhonemeQuality<Articulation, Region, Voicing>) -> Features {
+        let affricated = matches!(self.phone, Phone::Consonant { affricated: Some(_), .. });
+
+        let mut features = Features::consonant(self.consonant_quality(quality), affricated);
+        features.modify(self.diacritics.iter().copied(), self.length);
+
+        features
+    }
+
+    /// The distinctive features of a vowel charted at `quality`
+    /// Diphthongs only keep the values their two ends agree on
+    pub fn vowel_features(&self, quality: PhonemeQuality<Constriction, Place, Rounding>) -> Features {
+        let mut features = match self.phone.glide() {
+            Some([start, end]) => Features::vowel(start).intersection(&Features::vowel(end)),
+            None => Features::vowel(self.vowel_quality(quality))
+        };
+
+        features.modify(self.diacritics.iter().copied(), self.length);
+
+        features
     }
 }
 
@@ -131,7 +213,9 @@ fn from_raw<T: Category>(raw: Rc<[usize]>) -> Rc<[T]> {
     Rc::from(quality.as_slice())
 }
 
-#[derive(Clone, Debug)]
+pub type RawQuality = (Rc<[usize]>, Rc<[usize]>, Rc<[usize]>);
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct PhonemeQuality<A, B, C>(pub Rc<[A]>, pub Rc<[B]>, pub Rc<[C]>)
     where A: Category, B: Category, C: Category;
 
@@ -142,11 +226,11 @@ impl<A, B, C> PhonemeQuality<A, B, C>
         (&[][..], &[][..], &[][..]).into()
     }
 
-    pub fn into_raw(&self) -> (Rc<[usize]>, Rc<[usize]>, Rc<[usize]>) {
+    pub fn into_raw(&self) -> RawQuality {
         (into_raw(self.0.clone()), into_raw(self.1.clone()), into_raw(self.2.clone()))
     }
 
-    pub fn from_raw(raw: (Rc<[usize]>, Rc<[usize]>, Rc<[usize]>)) -> Self {
+    pub fn from_raw(raw: RawQuality) -> Self {
         let (a, b, c) = raw;
 
         Self(from_raw::<A>(a), from_raw::<B>(b), from_raw::<C>(c))
@@ -158,7 +242,7 @@ impl<A, B, C> PhonemeQuality<A, B, C>
     /// This function returns truthy
     /// Empty slices are shorthand for 'all variants'
     /// Because you would never search for an 
-    pub fn meets_restrictions(&self, mut restriction: PhonemeSelector<A, B, C>) -> bool {
+    pub fn meets_restrictions(&self, mut restriction: PhonemeQuality<A, B, C>) -> bool {
         if restriction.0.is_empty() //
             && restriction.1.is_empty() //
             && restriction.2.is_empty() {
@@ -190,9 +274,9 @@ impl<A, B, C> IntoIterator for PhonemeQuality<A, B, C>
 
     fn into_iter(self) -> Self::IntoIter {
         let mut queries = Vec::new();
-        for a in self.0.into_iter() {
-            for b in self.1.into_iter() {
-                for c in self.2.into_iter() {
+        for a in self.0.iter() {
+            for b in self.1.iter() {
+                for c in self.2.iter() {
                     queries.push((*a, *b, *c));
                 }
             }
@@ -310,5 +394,79 @@ impl<A, B, C> From<(&[A], &[B], &[C])> for PhonemeQuality<A, B, C>
     }
 }
 
+/// Selects phonemes by the categories they're charted in,
+/// further narrowed to those sharing every one of `features`
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PhonemeSelector<A, B, C> where A: Category, B: Category, C: Category {
+    pub quality: PhonemeQuality<A, B, C>,
+    pub features: Features
+}
+
+impl<A, B, C> PhonemeSelector<A, B, C> 
+    where A: Category, B: Category, C: Category {
+
+    pub fn blank() -> Self {
+        PhonemeQuality::blank().into()
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.quality == PhonemeQuality::blank() && self.features.is_empty()
+    }
+
+    pub fn meets(&self, quality: &PhonemeQuality<A, B, C>, features: &Features) -> bool {
+        quality.meets_restrictions(self.quality.clone()) && features.meets(&self.features)
+    }
+}
+
+impl<A, B, C> From<PhonemeQuality<A, B, C>> for PhonemeSelector<A, B, C> 
+    where A: Category, B: Category, C: Category {
+
+    fn from(quality: PhonemeQuality<A, B, C>) -> Self {
+        Self { quality, features: Features::default() }
+    }
+}
+
+// Serialized alongside the quality's own fields, so that selectors saved before features existed still load
+impl<A, B, C> serde::Serialize for PhonemeSelector<A, B, C>
+    where A: Category, B: Category, C: Category {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: serde::Serializer {
+
+        #[derive(serde::Serialize)]
+        struct Intermediate<'a, A, B, C> { a: &'a [A], b: &'a [B], c: &'a [C], features: &'a Features }
+
+        let PhonemeQuality(a, b, c) = &self.quality;
+
+        let intermediate = Intermediate { a, b, c, features: &self.features };
+
+        intermediate.serialize(serializer)
+    }
+}
+
+impl<'de, A, B, C> serde::Deserialize<'de> for PhonemeSelector<A, B, C>
+    where A: Category, B: Category, C: Category {
 
-pub type PhonemeSelector<A, B, C> = PhonemeQuality<A, B, C>;
\ No newline at end of file
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: serde::Deserializer<'de> {
+
+            #[derive(serde::Deserialize)]
+            struct Intermediate<A, B, C> { 
+                a: Vec<A>, 
+                b: Vec<B>, 
+                c: Vec<C>, 
+                #[serde(default)] 
+                features: Features 
+            }
+
+            let Intermediate { a, b, c, features } = Intermediate::deserialize(deserializer)?;
+
+            let quality = PhonemeQuality(
+                Rc::from(a.as_slice()), 
+                Rc::from(b.as_slice()), 
+                Rc::from(c.as_slice())
+            );
+
+            Ok(Self { quality, features })
+    }
+}
\ No newline at end of file
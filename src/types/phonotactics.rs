@@ -0,0 +1,296 @@
+use std::fmt;
+
+use slotmap::DefaultKey;
+
+use crate::types::{SoundChangeContext, PhonemeSelector, Lineage, Word};
+
+/// One position in a syllable template, such as the `(C)` in `(C)V`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Slot {
+    pub class: char,
+    pub optional: bool
+}
+
+#[derive(Clone, Debug)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Template(pub Vec<Slot>);
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for Slot { class, optional } in self.0.iter() {
+            if *optional {
+                write!(f, "({})", class)?;
+            } else {
+                write!(f, "{}", class)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A symbol that can appear in templates, standing in for every phoneme its context matches
+#[derive(Clone)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct SlotClass {
+    pub symbol: char,
+    pub context: SoundChangeContext
+}
+
+#[derive(Debug)]
+pub enum TemplateError {
+    UnknownClass(char),
+    Unbalanced
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownClass(symbol) => write!(f, "No class is named '{}'", symbol),
+            TemplateError::Unbalanced => write!(f, "Unbalanced parentheses")
+        }
+    }
+}
+
+impl std::error::Error for TemplateError { /*  */ }
+
+#[derive(Clone, Debug)]
+pub enum Violation {
+    Syllabification,
+    BannedCluster(Word)
+}
+
+#[derive(Clone)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Phonotactics {
+    pub classes: Vec<SlotClass>,
+    pub templates: Vec<Template>,
+    pub banned: Vec<Word>
+}
+
+impl Default for Phonotactics {
+    fn default() -> Self {
+        Self {
+            classes: vec![
+                SlotClass { 
+                    symbol: 'C', 
+                    context: SoundChangeContext::Consonant(PhonemeSelector::blank()) 
+                },
+                SlotClass { 
+                    symbol: 'V', 
+                    context: SoundChangeContext::Vowel(PhonemeSelector::blank()) 
+                }
+            ],
+            templates: Vec::new(),
+            banned: Vec::new()
+        }
+    }
+}
+
+impl Phonotactics {
+    pub fn class(&self, symbol: char) -> Option<&SoundChangeContext> {
+        self.classes
+            .iter()
+            .find(|class| class.symbol == symbol)
+            .map(|class| &class.context)
+    }
+
+    /// Parses a template such as `(C)(C)V(C)`, 
+    /// where parenthesized slots are optional
+    pub fn parse_template(&self, text: &str) -> Result<Template, TemplateError> {
+        let mut slots = Vec::new();
+
+        let mut optional = false;
+        for symbol in text.chars().filter(|symbol| !symbol.is_whitespace()) {
+            match symbol {
+                '(' if !optional => optional = true,
+                ')' if optional => optional = false,
+                '(' | ')' => return Err(TemplateError::Unbalanced),
+                symbol if self.class(symbol).is_some() => slots.push(Slot { class: symbol, optional }),
+                symbol => return Err(TemplateError::UnknownClass(symbol))
+            }
+        }
+
+        if optional { return Err(TemplateError::Unbalanced); }
+
+        Ok(Template(slots))
+    }
+
+    /// Every index that a syllable following `template` could end at,
+    /// given that it begins at `start`
+    fn template_ends(
+        &self, 
+        lineage: &Lineage<'_>, 
+        word: &[DefaultKey], 
+        template: &Template, 
+        start: usize) -> Vec<usize> {
+
+        let mut positions = vec![start];
+
+        for slot in template.0.iter() {
+            let class = match self.class(slot.class) {
+                Some(class) => class,
+                None => return Vec::new()
+            };
+
+            let mut next = Vec::new();
+            for position in positions {
+                if slot.optional { next.push(position); }
+
+                if let Some(end) = class.matches_after(lineage, word, position) {
+                    next.push(end);
+                }
+            }
+
+            next.sort();
+            next.dedup();
+
+            positions = next;
+        }
+
+        positions
+    }
+
+    /// Splits a word into syllables that each follow one of the templates
+    /// Returns the index that each syllable starts at, 
+    /// or `None` if the word can't be syllabified
+    pub fn syllabify(&self, lineage: &Lineage<'_>, word: &[DefaultKey]) -> Option<Vec<usize>> {
+        let mut previous = vec![None; word.len() + 1];
+
+        previous[0] = Some(0);
+
+        for start in 0..word.len() {
+            if previous[start].is_none() { continue; }
+
+            for template in self.templates.iter() {
+                for end in self.template_ends(lineage, word, template, start) {
+                    if end > start && previous[end].is_none() {
+                        previous[end] = Some(start);
+                    }
+                }
+            }
+        }
+
+        previous[word.len()]?;
+
+        let mut boundaries = Vec::new();
+
+        let mut idx = word.len();
+        while idx > 0 {
+            idx = previous[idx].unwrap();
+
+            boundaries.push(idx);
+        }
+
+        boundaries.reverse();
+
+        Some(boundaries)
+    }
+
+    /// Lists every way in which `word` breaks the language's phonotactics
+    /// Syllable structure is only checked once at least one template has been added
+    pub fn validate(&self, lineage: &Lineage<'_>, word: &[DefaultKey]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        if !self.templates.is_empty() && self.syllabify(lineage, word).is_none() {
+            violations.push(Violation::Syllabification);
+        }
+
+        for cluster in self.banned.iter().filter(|cluster| !cluster.is_empty()) {
+            let occurs = word.windows(cluster.len()).any(|window| {
+                window
+                    .iter()
+                    .zip(cluster.iter())
+                    .all(|(a, b)| lineage.is_same_sound(*a, *b))
+            });
+
+            if occurs {
+                violations.push(Violation::BannedCluster(cluster.clone()));
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use crate::types::parse_word;
+
+    fn phonotactics(templates: &[&str], banned: &[Word]) -> Phonotactics {
+        let mut phonotactics = Phonotactics::default();
+
+        phonotactics.templates = templates
+            .iter()
+            .map(|template| phonotactics.parse_template(template).unwrap())
+            .collect();
+
+        phonotactics.banned = banned.to_vec();
+        phonotactics
+    }
+
+    #[test]
+    fn templates_are_read_with_optional_slots() {
+        let phonotactics = Phonotactics::default();
+
+        let template = phonotactics.parse_template("(C) V (C)").unwrap();
+
+        assert_eq!(template.0, vec![
+            Slot { class: 'C', optional: true }, 
+            Slot { class: 'V', optional: false }, 
+            Slot { class: 'C', optional: true }
+        ]);
+        assert_eq!(format!("{}", template), "(C)V(C)");
+
+        assert!(matches!(phonotactics.parse_template("(CV"), Err(TemplateError::Unbalanced)));
+        assert!(matches!(phonotactics.parse_template("((C))V"), Err(TemplateError::Unbalanced)));
+        assert!(matches!(phonotactics.parse_template("CVN"), Err(TemplateError::UnknownClass('N'))));
+    }
+
+    #[test]
+    fn words_are_split_into_syllables_that_follow_a_template() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let word = |text: &str| parse_word(text, lineage.candidates()).unwrap();
+
+        let phonotactics = phonotactics(&["(C)V(C)"], &[]);
+
+        assert_eq!(phonotactics.syllabify(&lineage, &word("pata")), Some(vec![0, 2]));
+        assert_eq!(phonotactics.syllabify(&lineage, &word("patka")), Some(vec![0, 3]));
+        assert_eq!(phonotactics.syllabify(&lineage, &word("aa")), Some(vec![0, 1]));
+
+        // Neither consonant of an initial cluster can begin the syllable on its own
+        assert_eq!(phonotactics.syllabify(&lineage, &word("pta")), None);
+        assert!(matches!(phonotactics.validate(&lineage, &word("pta"))[..], [Violation::Syllabification]));
+    }
+
+    #[test]
+    fn syllable_structure_is_only_checked_once_there_are_templates() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let word = parse_word("pta", lineage.candidates()).unwrap();
+
+        assert!(phonotactics(&[], &[]).validate(&lineage, &word).is_empty());
+    }
+
+    #[test]
+    fn banned_clusters_are_found_anywhere_in_the_word() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let word = |text: &str| parse_word(text, lineage.candidates()).unwrap();
+
+        let phonotactics = phonotactics(&["(C)V(C)"], &[word("tk")]);
+
+        let violations = phonotactics.validate(&lineage, &word("patka"));
+        assert!(matches!(&violations[..], [Violation::BannedCluster(cluster)] if *cluster == word("tk")));
+
+        assert!(phonotactics.validate(&lineage, &word("takta")).is_empty());
+        assert!(phonotactics.validate(&lineage, &word("kat")).is_empty());
+    }
+}
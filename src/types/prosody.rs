@@ -0,0 +1,445 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use enum_iterator::Sequence;
+use slotmap::{DefaultKey, SlotMap};
+
+use crate::types::{Phoneme, Word, Lineage};
+use crate::types::parse_word;
+
+/// Chao tone letters, from the lowest pitch to the highest
+const TONE_LETTERS: [char; 5] = ['˩', '˨', '˧', '˦', '˥'];
+
+const PRIMARY: char = 'ˈ';
+const SECONDARY: char = 'ˌ';
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[derive(Sequence)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum Length {
+    #[default]
+    Short,
+    HalfLong,
+    Long
+}
+
+impl Length {
+    pub fn mark(&self) -> &'static str {
+        match self {
+            Length::Short => "",
+            Length::HalfLong => "ˑ",
+            Length::Long => "ː"
+        }
+    }
+
+    pub fn from_mark(mark: &str) -> Self {
+        match mark {
+            "ˑ" => Length::HalfLong,
+            "ː" => Length::Long,
+            _ => Length::Short
+        }
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Length::Short => "Short",
+            Length::HalfLong => "Half-Long",
+            Length::Long => "Long"
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[derive(Sequence)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum Stress {
+    #[default]
+    Unstressed,
+    Secondary,
+    Primary
+}
+
+impl Stress {
+    pub fn mark(&self) -> &'static str {
+        match self {
+            Stress::Unstressed => "",
+            Stress::Secondary => "ˌ",
+            Stress::Primary => "ˈ"
+        }
+    }
+}
+
+impl fmt::Display for Stress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Stress::Unstressed => "Unstressed",
+            Stress::Secondary => "Secondary",
+            Stress::Primary => "Primary"
+        })
+    }
+}
+
+/// The pitch levels a tone passes through, from 1 (lowest) to 5 (highest)
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Contour(pub Vec<u8>);
+
+impl Contour {
+    /// Reads a contour written as levels, such as "51"
+    pub fn from_levels(text: &str) -> Option<Self> {
+        let levels = text
+            .trim()
+            .chars()
+            .map(|level| match level.to_digit(10) {
+                Some(level @ 1..=5) => Some(level as u8),
+                _ => None
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        (!levels.is_empty()).then_some(Self(levels))
+    }
+
+    /// Reads the tone letters at the start of `text`
+    /// Returns the contour along with the number of bytes it was written with
+    pub fn from_letters(text: &str) -> Option<(Self, usize)> {
+        let mut levels = Vec::new();
+        let mut length = 0;
+
+        for letter in text.chars() {
+            match TONE_LETTERS.iter().position(|tone| *tone == letter) {
+                Some(level) => levels.push(level as u8 + 1),
+                None => break
+            }
+
+            length += letter.len_utf8();
+        }
+
+        (!levels.is_empty()).then_some((Self(levels), length))
+    }
+
+    pub fn levels(&self) -> String {
+        self.0.iter().map(|level| level.to_string()).collect()
+    }
+}
+
+impl fmt::Display for Contour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for level in self.0.iter() {
+            write!(f, "{}", TONE_LETTERS[(*level as usize).clamp(1, 5) - 1])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A named entry in a language's tone inventory
+#[derive(Clone, Default)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Tone {
+    pub name: String,
+    pub contour: Contour
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Syllable {
+    pub stress: Stress,
+    pub tone: Option<Contour>
+}
+
+/// The stress and tone of a word's syllables,
+/// each keyed by the index of the syllable's nucleus
+/// Syllables that aren't listed are unstressed and toneless
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Prosody(pub BTreeMap<usize, Syllable>);
+
+/// Vowels and diphthongs carry a syllable's stress and tone
+pub fn is_nucleus(phoneme: &Phoneme) -> bool {
+    phoneme.phone.is_vowel() || phoneme.phone.is_diphthong()
+}
+
+impl Prosody {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn syllable(&self, nucleus: usize) -> Syllable {
+        self.0.get(&nucleus).cloned().unwrap_or_default()
+    }
+
+    /// Replaces the syllable at `nucleus`, forgetting it if it's unmarked
+    pub fn set_syllable(&mut self, nucleus: usize, syllable: Syllable) {
+        if syllable == Syllable::default() {
+            self.0.remove(&nucleus);
+        } else {
+            self.0.insert(nucleus, syllable);
+        }
+    }
+
+    /// The nucleus of the syllable that the segment at `idx` belongs to
+    /// Consonants belong to the following nucleus, or the preceding one at the end of a word
+    pub fn nucleus(lineage: &Lineage<'_>, word: &[DefaultKey], idx: usize) -> Option<usize> {
+        let nucleus = |idx: &usize| lineage.phoneme(word[*idx]).map_or(false, is_nucleus);
+
+        (idx..word.len()).find(nucleus).or_else(|| (0..idx).rev().find(nucleus))
+    }
+
+    /// Drops any syllable whose nucleus is no longer a vowel
+    pub fn retain_nuclei(&mut self, phonemes: &SlotMap<DefaultKey, Phoneme>, word: &[DefaultKey]) {
+        self.0.retain(|idx, _| {
+            word.get(*idx)
+                .and_then(|id| phonemes.get(*id))
+                .map_or(false, is_nucleus)
+        });
+    }
+}
+
+/// Writes a word with its stress marks and tone letters
+/// A stress mark is placed before the consonant that leads into its syllable,
+/// tone letters follow the nucleus
+pub fn prosodic_word_to_string(
+    phonemes: &SlotMap<DefaultKey, Phoneme>,
+    word: &[DefaultKey],
+    prosody: &Prosody) -> String {
+
+    let nucleus = |idx: usize| phonemes.get(word[idx]).map_or(false, is_nucleus);
+
+    let mut marks = BTreeMap::new();
+    for (idx, syllable) in prosody.0.iter().filter(|(idx, _)| **idx < word.len()) {
+        let onset = match idx.checked_sub(1) {
+            Some(previous) if !nucleus(previous) => previous,
+            _ => *idx
+        };
+
+        marks.insert(onset, syllable.stress.mark());
+    }
+
+    let mut content = String::new();
+    for (idx, id) in word.iter().enumerate() {
+        if let Some(mark) = marks.get(&idx) {
+            content.push_str(mark);
+        }
+
+        match phonemes.get(*id) {
+            Some(phoneme) => content.push_str(format!("{}", phoneme).as_str()),
+            None => content.push('?')
+        }
+
+        if let Some(tone) = prosody.0.get(&idx).and_then(|syllable| syllable.tone.as_ref()) {
+            content.push_str(format!("{}", tone).as_str());
+        }
+    }
+
+    content
+}
+
+/// Splits `text` into phonemes like `parse_word`, reading stress marks and tone letters along the way
+/// A stress mark applies to the next nucleus, tone letters to the previous one
+pub fn parse_prosodic_word(
+    text: &str,
+    lineage: &Lineage<'_>) -> Option<(Word, Prosody)> {
+
+    let candidates = lineage.candidates().collect::<Vec<_>>();
+
+    let mut word = Word::new();
+    let mut prosody = Prosody::default();
+
+    let mut stress = None;
+
+    let mut remaining = text.trim();
+    while !remaining.is_empty() {
+        let next = remaining.chars().next()?;
+
+        if next == PRIMARY || next == SECONDARY {
+            let _ = stress.insert(if next == PRIMARY { Stress::Primary } else { Stress::Secondary });
+
+            remaining = &remaining[next.len_utf8()..];
+        } else if let Some((contour, length)) = Contour::from_letters(remaining) {
+            let nucleus = (0..word.len())
+                .rev()
+                .find(|idx| lineage.phoneme(word[*idx]).map_or(false, is_nucleus))?;
+
+            let mut syllable = prosody.syllable(nucleus);
+            let _ = syllable.tone.insert(contour);
+
+            prosody.set_syllable(nucleus, syllable);
+
+            remaining = &remaining[length..];
+        } else {
+            let end = remaining
+                .find(|c: char| c == PRIMARY || c == SECONDARY || TONE_LETTERS.contains(&c))
+                .unwrap_or(remaining.len());
+
+            let start = word.len();
+
+            word.extend(parse_word(&remaining[..end], candidates.iter().cloned())?);
+
+            if let Some(stress) = stress.take() {
+                let nucleus = (start..word.len())
+                    .find(|idx| lineage.phoneme(word[*idx]).map_or(false, is_nucleus))?;
+
+                let mut syllable = prosody.syllable(nucleus);
+                syllable.stress = stress;
+
+                prosody.set_syllable(nucleus, syllable);
+            }
+
+            remaining = &remaining[end..];
+        }
+    }
+
+    Some((word, prosody))
+}
+
+/// The stress and tone a sound change requires of its source's syllable,
+/// or gives to its destination's syllable
+/// Unset fields match any syllable, or leave it as it was
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Suprasegmentals {
+    pub stress: Option<Stress>,
+    pub tone: Option<Contour>
+}
+
+impl Suprasegmentals {
+    pub fn is_empty(&self) -> bool {
+        self.stress.is_none() && self.tone.is_none()
+    }
+
+    pub fn matches(&self, syllable: &Syllable) -> bool {
+        self.stress.map_or(true, |stress| stress == syllable.stress) //
+            && self.tone.as_ref().map_or(true, |tone| Some(tone) == syllable.tone.as_ref())
+    }
+
+    pub fn apply(&self, syllable: &mut Syllable) {
+        if let Some(stress) = self.stress {
+            syllable.stress = stress;
+        }
+
+        if let Some(tone) = &self.tone {
+            let _ = syllable.tone.insert(tone.clone());
+        }
+    }
+
+    /// Splits the suprasegmentals off of a phoneme written in a rule, 
+    /// such as `ˈa`, `a˥˩` or `a[Unstressed]`
+    pub fn parse(text: &str) -> Option<(&str, Self)> {
+        let mut suprasegmentals = Self::default();
+
+        let mut text = text.trim();
+
+        if let Some(rest) = text.strip_prefix(PRIMARY) {
+            let _ = suprasegmentals.stress.insert(Stress::Primary);
+            text = rest;
+        } else if let Some(rest) = text.strip_prefix(SECONDARY) {
+            let _ = suprasegmentals.stress.insert(Stress::Secondary);
+            text = rest;
+        }
+
+        if let Some((rest, name)) = text.strip_suffix(']').and_then(|text| text.rsplit_once('[')) {
+            let stress = enum_iterator::all::<Stress>()
+                .find(|stress| format!("{:?}", stress) == name.trim())?;
+
+            let _ = suprasegmentals.stress.insert(stress);
+            text = rest.trim_end();
+        }
+
+        if let Some(start) = text.find(|c: char| TONE_LETTERS.contains(&c)) {
+            let (contour, length) = Contour::from_letters(&text[start..])?;
+
+            if start + length != text.len() { return None; }
+
+            let _ = suprasegmentals.tone.insert(contour);
+            text = &text[..start];
+        }
+
+        Some((text, suprasegmentals))
+    }
+
+    /// The inverse of `parse`
+    pub fn write(&self, symbol: &str) -> String {
+        match self.stress {
+            Some(Stress::Unstressed) => format!("{}{}[Unstressed]", 
+                symbol, 
+                self.tone.as_ref().map_or(String::new(), |tone| format!("{}", tone))),
+            stress => format!("{}{}{}", 
+                stress.map_or("", |stress| stress.mark()), 
+                symbol, 
+                self.tone.as_ref().map_or(String::new(), |tone| format!("{}", tone)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use crate::types::{SoundChange, SoundChangeContext, Change, PhonemeSelector};
+
+    fn sequence(lineage: &Lineage<'_>, src: &str, dst: &str, context: (SoundChangeContext, SoundChangeContext)) -> SoundChange {
+        let word = |text: &str| parse_word(text, lineage.candidates()).unwrap();
+
+        SoundChange {
+            change: Change::Sequence { src: word(src), dst: word(dst) },
+            context,
+            prosody: Default::default(),
+            application: Default::default()
+        }
+    }
+
+    /// The word that `sound_change` makes of `text`, written with its stress and tone
+    fn apply(lineage: &Lineage<'_>, sound_change: &SoundChange, text: &str) -> String {
+        let (mut word, mut prosody) = parse_prosodic_word(text, lineage).unwrap();
+
+        sound_change.apply(lineage, &mut word, &mut prosody, None);
+
+        prosodic_word_to_string(lineage.phonemes(), &word, &prosody)
+    }
+
+    #[test]
+    fn syllables_are_keyed_by_their_nucleus() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let (word, prosody) = parse_prosodic_word("paˈta˥˩ˌkai˧", &lineage).unwrap();
+
+        assert_eq!(word.len(), 7);
+        assert_eq!(prosody.0.keys().copied().collect::<Vec<_>>(), vec![3, 5, 6]);
+
+        assert_eq!(prosody.syllable(1), Syllable::default());
+        assert_eq!(prosody.syllable(3), Syllable { stress: Stress::Primary, tone: Some(Contour(vec![5, 1])) });
+        assert_eq!(prosody.syllable(5), Syllable { stress: Stress::Secondary, tone: None });
+        assert_eq!(prosody.syllable(6), Syllable { stress: Stress::Unstressed, tone: Some(Contour(vec![3])) });
+
+        assert_eq!(prosodic_word_to_string(&state.phonemes, &word, &prosody), "paˈta˥˩ˌkai˧");
+    }
+
+    #[test]
+    fn stress_and_tone_follow_their_vowel_past_an_insertion() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let prothesis = sequence(&lineage, "", "ə", (SoundChangeContext::Boundary, SoundChangeContext::Unrestricted));
+        assert_eq!(apply(&lineage, &prothesis, "ˈpa˥ta"), "əˈpa˥ta");
+
+        let consonant = SoundChangeContext::Consonant(PhonemeSelector::blank());
+        let epenthesis = sequence(&lineage, "", "ə", (consonant.clone(), consonant));
+        assert_eq!(apply(&lineage, &epenthesis, "ˈpa˥ktaˌka"), "ˈpa˥kətaˌka");
+    }
+
+    #[test]
+    fn stress_and_tone_follow_their_vowel_past_a_deletion() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let aphaeresis = sequence(&lineage, "a", "", (SoundChangeContext::Boundary, SoundChangeContext::Unrestricted));
+        assert_eq!(apply(&lineage, &aphaeresis, "apaˈta˥"), "paˈta˥");
+
+        // A deleted vowel takes its own stress and tone with it
+        let apocope = sequence(&lineage, "a", "", (SoundChangeContext::Unrestricted, SoundChangeContext::Boundary));
+        assert_eq!(apply(&lineage, &apocope, "ˈpa˥ta˩"), "ˈpa˥t");
+    }
+}
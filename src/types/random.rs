@@ -0,0 +1,50 @@
+/// A small SplitMix64 generator
+/// Sequences depend only on the seed, so batches can be reproduced on any machine
+pub struct Random(u64);
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A float in the range [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// An integer in the range [0, bound)
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Picks an item with probability proportional to its weight
+    /// Returns `None` if no item has a positive weight
+    pub fn weighted<'a, T>(&mut self, items: &'a [(T, f32)]) -> Option<&'a T> {
+        let total = items
+            .iter()
+            .map(|(_, weight)| weight.max(0.))
+            .sum::<f32>();
+
+        if total <= 0. { return None; }
+
+        let mut target = self.next_f32() * total;
+        for (item, weight) in items.iter() {
+            let weight = weight.max(0.);
+
+            if target < weight { return Some(item); }
+
+            target -= weight;
+        }
+
+        items.iter().rev().find(|(_, weight)| *weight > 0.).map(|(item, _)| item)
+    }
+}
@@ -0,0 +1,420 @@
+use std::fmt;
+
+use slotmap::{DefaultKey, SlotMap};
+
+use crate::types::{Phoneme, Word, SoundChangeContext, Lineage};
+
+/// Written between two graphemes that would otherwise be read as a multigraph,
+/// as in the "n'g" of a language that spells /ŋ/ as "ng"
+/// A language that spells a phoneme with it, such as /ʔ/, reads it as that phoneme wherever it can
+pub const SEPARATOR: char = '\'';
+
+#[derive(Clone, Debug)]
+pub enum Ambiguity {
+    /// Phonemes that sound different are spelled the same way
+    SharedGrapheme(String),
+    /// The text at this byte offset could also be split differently
+    Segmentation { position: usize, alternatives: Vec<String> },
+    /// The grapheme at this byte offset isn't used in its surroundings
+    Irregular { position: usize, grapheme: String }
+}
+
+impl fmt::Display for Ambiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ambiguity::SharedGrapheme(grapheme) => 
+                write!(f, "'{}' spells more than one phoneme", grapheme),
+            Ambiguity::Segmentation { position, alternatives } => 
+                write!(f, "Could be read as any of '{}' at position {}", alternatives.join("', '"), position),
+            Ambiguity::Irregular { position, grapheme } => 
+                write!(f, "'{}' at position {} isn't normally written in this context", grapheme, position)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Unparseable {
+    pub position: usize,
+    pub remaining: String
+}
+
+impl fmt::Display for Unparseable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unable to read '{}' at position {}", self.remaining, self.position)
+    }
+}
+
+impl std::error::Error for Unparseable { /*  */ }
+
+pub struct Deromanization {
+    pub word: Word,
+    pub ambiguities: Vec<Ambiguity>
+}
+
+/// A grapheme that is only used when the segments around a phoneme satisfy both contexts
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct ContextualSpelling {
+    pub grapheme: String,
+    pub context: (SoundChangeContext, SoundChangeContext)
+}
+
+impl ContextualSpelling {
+    pub fn matches(&self, lineage: &Lineage<'_>, word: &[DefaultKey], idx: usize) -> bool {
+        let (before, after) = &self.context;
+
+        before.matches_before(lineage, word, idx).is_some() && //
+            after.matches_after(lineage, word, idx + 1).is_some()
+    }
+
+    /// The environment in the conventional `before_after` notation
+    /// Empty if the spelling is unconditioned
+    pub fn environment(&self, phonemes: &SlotMap<DefaultKey, Phoneme>) -> String {
+        match &self.context {
+            (SoundChangeContext::Unrestricted, SoundChangeContext::Unrestricted) => String::new(),
+            (before, after) => format!("{}_{}", before.write(phonemes), after.write(phonemes))
+        }
+    }
+}
+
+/// A phoneme's grapheme, or its symbol if it hasn't been given one
+pub fn spelling(phoneme: &Phoneme) -> String {
+    if phoneme.grapheme.is_empty() {
+        format!("{}", phoneme)
+    } else {
+        phoneme.grapheme.clone()
+    }
+}
+
+/// The spelling of the segment at `idx`, taking its surroundings into account
+/// The first contextual spelling whose environment matches is used
+pub fn spell(lineage: &Lineage<'_>, word: &[DefaultKey], idx: usize) -> String {
+    match lineage.phonemes().get(word[idx]) {
+        Some(phoneme) => phoneme.spellings
+            .iter()
+            .find(|spelling| !spelling.grapheme.is_empty() && spelling.matches(lineage, word, idx))
+            .map_or_else(|| spelling(phoneme), |spelling| spelling.grapheme.clone()),
+        None => String::from("?")
+    }
+}
+
+struct Grapheme {
+    id: DefaultKey,
+    text: String,
+    conditioned: bool
+}
+
+/// The spellings of every phoneme that a language can write
+pub struct Orthography {
+    graphemes: Vec<Grapheme>
+}
+
+impl Orthography {
+    /// Phonemes that appear earlier take precedence when two of them sound alike
+    pub fn new(
+        phonemes: &SlotMap<DefaultKey, Phoneme>, 
+        ids: impl IntoIterator<Item = DefaultKey>) -> Self {
+
+        let mut graphemes: Vec<Grapheme> = Vec::new();
+        
+        for id in ids {
+            let phoneme = match phonemes.get(id) {
+                Some(phoneme) => phoneme,
+                None => continue
+            };
+
+            let regular = (spelling(phoneme), false);
+            let contextual = phoneme.spellings
+                .iter()
+                .map(|spelling| (spelling.grapheme.clone(), true));
+
+            for (text, conditioned) in Some(regular).into_iter().chain(contextual) {
+                let duplicate = graphemes.iter().any(|other| {
+                    other.text == text && //
+                        format!("{}", phonemes[other.id]) == format!("{}", phoneme)
+                });
+
+                if !duplicate && !text.is_empty() {
+                    graphemes.push(Grapheme { id, text, conditioned });
+                }
+            }
+        }
+
+        Self { graphemes }
+    }
+
+    fn matches_at<'a>(&'a self, text: &'a str, position: usize) -> impl Iterator<Item = &'a Grapheme> + 'a {
+        self.graphemes
+            .iter()
+            .filter(move |grapheme| text[position..].starts_with(grapheme.text.as_str()))
+    }
+
+    /// Splits `text` into graphemes, preferring the longest grapheme at each point
+    /// A shorter grapheme is only taken if the longer one would leave the rest unreadable
+    /// Returns the byte offset of each grapheme along with every phoneme it could spell
+    fn segment<'a>(&'a self, text: &'a str, ambiguities: &mut Vec<Ambiguity>) -> Result<Vec<(usize, Vec<&'a Grapheme>)>, Unparseable> {
+        let boundaries = text
+            .char_indices()
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        // Whether the text from each byte offset onward can be read
+        let mut parseable = vec![false; text.len() + 1];
+
+        parseable[text.len()] = true;
+
+        for position in boundaries.iter().rev().copied() {
+            parseable[position] = self.matches_at(text, position)
+                .any(|grapheme| parseable[position + grapheme.text.len()]) //
+                || (text[position..].starts_with(SEPARATOR) && parseable[position + SEPARATOR.len_utf8()]);
+        }
+
+        if !parseable[0] {
+            let mut reachable = vec![false; text.len() + 1];
+
+            reachable[0] = true;
+
+            for position in boundaries.iter().copied() {
+                if !reachable[position] { continue; }
+
+                if text[position..].starts_with(SEPARATOR) {
+                    reachable[position + SEPARATOR.len_utf8()] = true;
+                }
+
+                for grapheme in self.matches_at(text, position) {
+                    reachable[position + grapheme.text.len()] = true;
+                }
+            }
+
+            let position = (0..text.len())
+                .rev()
+                .find(|position| reachable[*position])
+                .unwrap_or(0);
+
+            return Err(Unparseable { position, remaining: String::from(&text[position..]) });
+        }
+
+        let mut segments = Vec::new();
+
+        let mut position = 0;
+        while position < text.len() {
+            let options = self.matches_at(text, position)
+                .filter(|grapheme| parseable[position + grapheme.text.len()])
+                .collect::<Vec<_>>();
+
+            // Graphemes are tried before the separator, which may spell a phoneme of its own
+            if options.is_empty() {
+                position += SEPARATOR.len_utf8();
+
+                continue;
+            }
+
+            let longest = options
+                .iter()
+                .map(|grapheme| grapheme.text.len())
+                .max()
+                .unwrap();
+
+            let mut alternatives = options
+                .iter()
+                .map(|grapheme| grapheme.text.clone())
+                .collect::<Vec<_>>();
+
+            alternatives.sort();
+            alternatives.dedup();
+
+            if alternatives.len() > 1 {
+                ambiguities.push(Ambiguity::Segmentation { position, alternatives });
+            }
+
+            let readings = options
+                .into_iter()
+                .filter(|grapheme| grapheme.text.len() == longest)
+                .collect::<Vec<_>>();
+
+            segments.push((position, readings));
+
+            position += longest;
+        }
+
+        Ok(segments)
+    }
+
+    /// Reads `text` as a word, splitting it into graphemes as `segment` does
+    /// Graphemes shared by several phonemes are resolved by the contexts they're written in
+    pub fn deromanize(&self, lineage: &Lineage<'_>, text: &str) -> Result<Deromanization, Unparseable> {
+        let mut ambiguities = Vec::new();
+
+        let segments = self.segment(text.trim(), &mut ambiguities)?;
+
+        let mut word = segments
+            .iter()
+            .map(|(_, readings)| readings[0].id)
+            .collect::<Word>();
+
+        // Now that the whole word is known, keep the readings that would be spelled this way
+        for (idx, (position, readings)) in segments.into_iter().enumerate() {
+            let text = readings[0].text.clone();
+
+            let consistent = readings
+                .iter()
+                .filter(|grapheme| {
+                    word[idx] = grapheme.id;
+
+                    spell(lineage, &word, idx) == text
+                })
+                .map(|grapheme| grapheme.id)
+                .collect::<Vec<_>>();
+
+            match consistent.first() {
+                Some(id) => {
+                    word[idx] = *id;
+
+                    if consistent.len() > 1 {
+                        ambiguities.push(Ambiguity::SharedGrapheme(text));
+                    }
+                },
+                None => {
+                    word[idx] = readings[0].id;
+
+                    ambiguities.push(Ambiguity::Irregular { position, grapheme: text });
+                }
+            }
+        }
+
+        Ok(Deromanization { word, ambiguities })
+    }
+
+    /// Every grapheme that spells more than one distinct phoneme regardless of context
+    pub fn shared_graphemes(&self) -> Vec<String> {
+        let regular = self.graphemes
+            .iter()
+            .filter(|grapheme| !grapheme.conditioned)
+            .collect::<Vec<_>>();
+
+        let mut shared = regular
+            .iter()
+            .filter(|grapheme| regular
+                .iter()
+                .any(|other| other.id != grapheme.id && other.text == grapheme.text))
+            .map(|grapheme| grapheme.text.clone())
+            .collect::<Vec<_>>();
+
+        shared.sort();
+        shared.dedup();
+        shared
+    }
+
+    /// Spells out a word, separating graphemes that would otherwise run together
+    pub fn romanize(&self, lineage: &Lineage<'_>, word: &[DefaultKey]) -> String {
+        let spellings = (0..word.len())
+            .map(|idx| spell(lineage, word, idx))
+            .collect::<Vec<_>>();
+
+        let faithful = |text: &str, expected: &[String]| {
+            self.segment(text, &mut Vec::new()).map_or(false, |segments| {
+                segments.len() == expected.len() && segments
+                    .iter()
+                    .zip(expected.iter())
+                    .all(|((_, readings), spelling)| readings[0].text == *spelling)
+            })
+        };
+
+        let mut text = String::new();
+
+        for (idx, spelling) in spellings.iter().enumerate() {
+            let joined = format!("{}{}", text, spelling);
+            let separated = format!("{}{}{}", text, SEPARATOR, spelling);
+
+            text = if idx > 0 && !faithful(&joined, &spellings[0..=idx]) && faithful(&separated, &spellings[0..=idx]) {
+                separated
+            } else {
+                joined
+            };
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Phone;
+
+    /// A phoneme for each `(symbol, grapheme)`, in order
+    /// Phonemes are only ever consonants, since romanization doesn't depend on quality
+    fn phonemes(spellings: &[(&str, &str)]) -> (SlotMap<DefaultKey, Phoneme>, Vec<DefaultKey>) {
+        let mut phonemes = SlotMap::new();
+
+        let ids = spellings
+            .iter()
+            .map(|(symbol, grapheme)| {
+                let mut phoneme = Phoneme::new(*symbol, Phone::consonant());
+                phoneme.grapheme = String::from(*grapheme);
+
+                let id = phonemes.insert(phoneme);
+                phonemes[id].set_id(id);
+                id
+            })
+            .collect();
+
+        (phonemes, ids)
+    }
+
+    #[test]
+    fn multigraphs_are_kept_apart_with_the_separator() {
+        let (phonemes, ids) = phonemes(&[("a", ""), ("n", ""), ("g", ""), ("ŋ", "ng")]);
+        let [a, n, g, ng] = ids[..] else { unreachable!() };
+
+        let orthography = Orthography::new(&phonemes, ids.iter().copied());
+        let lineage = Lineage::new(&phonemes);
+
+        assert_eq!(orthography.romanize(&lineage, &[a, ng, a]), "anga");
+        assert_eq!(orthography.romanize(&lineage, &[a, n, g, a]), "an'ga");
+
+        assert_eq!(orthography.deromanize(&lineage, "anga").unwrap().word, vec![a, ng, a]);
+        assert_eq!(orthography.deromanize(&lineage, "an'ga").unwrap().word, vec![a, n, g, a]);
+    }
+
+    #[test]
+    fn a_separator_that_spells_a_phoneme_is_read_as_it() {
+        let (phonemes, ids) = phonemes(&[("a", ""), ("ʔ", "'")]);
+        let [a, glottal] = ids[..] else { unreachable!() };
+
+        let orthography = Orthography::new(&phonemes, ids.iter().copied());
+        let lineage = Lineage::new(&phonemes);
+
+        assert_eq!(orthography.romanize(&lineage, &[a, glottal, a]), "a'a");
+        assert_eq!(orthography.deromanize(&lineage, "a'a").unwrap().word, vec![a, glottal, a]);
+    }
+
+    #[test]
+    fn unreadable_text_is_reported_where_reading_stops() {
+        let (phonemes, ids) = phonemes(&[("a", ""), ("ŋ", "ng")]);
+
+        let orthography = Orthography::new(&phonemes, ids.iter().copied());
+        let lineage = Lineage::new(&phonemes);
+
+        let unparseable = orthography.deromanize(&lineage, "anag").err().unwrap();
+
+        assert_eq!((unparseable.position, unparseable.remaining.as_str()), (1, "nag"));
+    }
+
+    #[test]
+    fn graphemes_spelling_several_phonemes_are_ambiguous() {
+        let (phonemes, ids) = phonemes(&[("a", ""), ("k", "c"), ("s", "c")]);
+        let [a, k, _] = ids[..] else { unreachable!() };
+
+        let orthography = Orthography::new(&phonemes, ids.iter().copied());
+        let lineage = Lineage::new(&phonemes);
+
+        assert_eq!(orthography.shared_graphemes(), vec![String::from("c")]);
+
+        // The phoneme listed first is read, but the reading is flagged
+        let deromanization = orthography.deromanize(&lineage, "ca").unwrap();
+
+        assert_eq!(deromanization.word, vec![k, a]);
+        assert!(matches!(&deromanization.ambiguities[..], [Ambiguity::SharedGrapheme(grapheme)] if grapheme == "c"));
+    }
+}
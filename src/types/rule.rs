@@ -0,0 +1,558 @@
+use std::fmt;
+
+use enum_iterator::all;
+use slotmap::{DefaultKey, SlotMap};
+
+use crate::types::{Phoneme, PhonemeQuality, RawQuality, SoundChange, SoundChangeContext, Change, Application, Lineage};
+use crate::types::{parse_word, word_to_string, Length, Suprasegmentals, Features, PhonemeSelector, Word};
+use crate::types::{Language, Stage, Period};
+use crate::types::category::Category;
+use crate::types::category::{Articulation, Region, Voicing, Constriction, Place, Rounding};
+
+/// What a rule changes, before its products have been added to a dialect's inventory
+pub enum RuleChange {
+    /// `dst` is charted in the dialect with `quality`
+    Substitution { src: DefaultKey, dst: Phoneme, quality: RawQuality },
+    /// The products are only resolved once the dialect that the shift belongs to is known
+    Shift { src: SoundChangeContext, dst: SoundChangeContext },
+    /// Each phoneme of `dst` is charted in the dialect with its quality
+    Sequence { src: Vec<DefaultKey>, dst: Vec<(Phoneme, RawQuality)> },
+    Metathesis { src: Vec<DefaultKey> },
+    Gemination { src: DefaultKey },
+    Degemination { src: DefaultKey }
+}
+
+/// Written for an empty source or destination, such as `h > ∅`
+pub const EMPTY: &str = "∅";
+
+/// A sound change whose products have yet to be added to a dialect's inventory
+pub struct Rule {
+    pub change: RuleChange,
+    pub context: (SoundChangeContext, SoundChangeContext),
+    pub prosody: (Suprasegmentals, Suprasegmentals),
+    pub application: Application
+}
+
+impl Rule {
+    /// True if the rule would be added as `sound_change`
+    /// Phonemes only need to sound the same, since products are copied when a rule is added
+    pub fn describes(&self, phonemes: &SlotMap<DefaultKey, Phoneme>, sound_change: &SoundChange) -> bool {
+        let sounds = |phoneme: &Phoneme, id: &DefaultKey| phonemes
+            .get(*id)
+            .map_or(false, |other| other.phone == phoneme.phone && format!("{}", other) == format!("{}", phoneme));
+
+        let same = |a: &DefaultKey, b: &DefaultKey| a == b || phonemes.get(*a).map_or(false, |a| sounds(a, b));
+
+        let same_sequence = |a: &[DefaultKey], b: &[DefaultKey]| {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b))
+        };
+
+        let change = match (&self.change, &sound_change.change) {
+            (RuleChange::Substitution { src, dst, .. }, Change::Substitution { src: other, dst: product }) => 
+                same(src, other) && sounds(dst, product),
+            (RuleChange::Shift { src, dst }, Change::Shift { src: other, dst: product, .. }) => 
+                src == other && dst == product,
+            (RuleChange::Sequence { src, dst }, Change::Sequence { src: other, dst: products }) => 
+                same_sequence(src, other) //
+                    && dst.len() == products.len() //
+                    && dst.iter().zip(products).all(|((dst, _), product)| sounds(dst, product)),
+            (RuleChange::Metathesis { src }, Change::Metathesis { src: other }) => same_sequence(src, other),
+            (RuleChange::Gemination { src }, Change::Gemination { src: other }) //
+                | (RuleChange::Degemination { src }, Change::Degemination { src: other }) => same(src, other),
+            _ => false
+        };
+
+        // Exceptions can't be written as text, so they're left out
+        let (application, other) = (&self.application, &sound_change.application);
+
+        change //
+            && self.context == sound_change.context //
+            && self.prosody == sound_change.prosody //
+            && application.probability == other.probability //
+            && application.seed == other.seed //
+            && application.diffusion == other.diffusion
+    }
+}
+
+#[derive(Debug)]
+pub enum RuleError {
+    Syntax(String),
+    UnknownPhoneme(String),
+    UnknownCategory(String),
+    MixedSelector(String),
+    Suprasegmental(String)
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Syntax(content) => write!(f, "{}", content),
+            RuleError::UnknownPhoneme(content) => write!(f, "Unknown phoneme '{}'", content),
+            RuleError::UnknownCategory(content) => write!(f, "Unknown category '{}'", content),
+            RuleError::MixedSelector(content) => 
+                write!(f, "Selector '[{}]' mixes consonant and vowel categories", content),
+            RuleError::Suprasegmental(content) => 
+                write!(f, "Unable to read the stress or tone of '{}'", content)
+        }
+    }
+}
+
+impl std::error::Error for RuleError { /*  */ }
+
+fn parse_phoneme(text: &str, lineage: &Lineage<'_>) -> Result<DefaultKey, RuleError> {
+    let text = text.trim();
+
+    match parse_word(text, lineage.candidates()) {
+        Some(word) if word.len() == 1 => Ok(word[0]),
+        Some(word) if word.is_empty() => 
+            Err(RuleError::Syntax(String::from("Expected a phoneme"))),
+        _ => Err(RuleError::UnknownPhoneme(String::from(text)))
+    }
+}
+
+/// Reads a phoneme along with the stress and tone written around it
+fn parse_suprasegmental(text: &str) -> Result<(&str, Suprasegmentals), RuleError> {
+    Suprasegmentals::parse(text)
+        .ok_or_else(|| RuleError::Suprasegmental(String::from(text.trim())))
+}
+
+/// Destinations may lengthen a phoneme that only exists as a short sound
+fn parse_lengthened(text: &str, lineage: &Lineage<'_>) -> Result<(DefaultKey, Option<Length>), RuleError> {
+    let text = text.trim();
+
+    match parse_phoneme(text, lineage) {
+        Err(RuleError::UnknownPhoneme(content)) => {
+            let (base, length) = match text.char_indices().last() {
+                Some((idx, mark)) if Length::from_mark(&text[idx..]) != Length::Short => 
+                    (&text[..idx], Length::from_mark(&mark.to_string())),
+                _ => return Err(RuleError::UnknownPhoneme(content))
+            };
+
+            parse_phoneme(base, lineage).map(|id| (id, Some(length)))
+        },
+        parsed => parsed.map(|id| (id, None))
+    }
+}
+
+fn find_category<T: Category>(name: &str) -> Option<T> {
+    all::<T>().find(|variant| format!("{:?}", variant) == name)
+}
+
+fn push_category<T: Category>(axis: &mut Vec<T>, name: &str) -> bool {
+    match find_category::<T>(name) {
+        Some(variant) => { axis.push(variant); true },
+        None => false
+    }
+}
+
+fn parse_selector(content: &str) -> Result<SoundChangeContext, RuleError> {
+    let (mut a, mut b, mut c) = (Vec::new(), Vec::new(), Vec::new());
+    let (mut d, mut e, mut f) = (Vec::new(), Vec::new(), Vec::new());
+
+    let mut features = Features::default();
+
+    for name in content.split_whitespace() {
+        if let Some((feature, value)) = Features::parse_value(name) {
+            features.set(feature, value);
+            continue;
+        }
+
+        let found = push_category::<Articulation>(&mut a, name) //
+            || push_category::<Region>(&mut b, name) //
+            || push_category::<Voicing>(&mut c, name) //
+            || push_category::<Constriction>(&mut d, name) //
+            || push_category::<Place>(&mut e, name) //
+            || push_category::<Rounding>(&mut f, name);
+
+        if !found {
+            return Err(RuleError::UnknownCategory(String::from(name)));
+        }
+    }
+
+    let consonant = !(a.is_empty() && b.is_empty() && c.is_empty());
+    let vowel = !(d.is_empty() && e.is_empty() && f.is_empty());
+
+    match (consonant, vowel) {
+        (true, false) => Ok(SoundChangeContext::Consonant(PhonemeSelector { 
+            quality: (&a[..], &b[..], &c[..]).into(), 
+            features 
+        })),
+        (false, true) => Ok(SoundChangeContext::Vowel(PhonemeSelector { 
+            quality: (&d[..], &e[..], &f[..]).into(), 
+            features 
+        })),
+        (true, true) => Err(RuleError::MixedSelector(String::from(content.trim()))),
+        // Selectors made only of features may match consonants and vowels alike
+        (false, false) if !features.is_empty() => Ok(SoundChangeContext::Natural(features)),
+        (false, false) => Err(RuleError::Syntax(String::from("Empty selector '[]'")))
+    }
+}
+
+fn parse_environment(text: &str, lineage: &Lineage<'_>) -> Result<SoundChangeContext, RuleError> {
+    let candidates = lineage.candidates().collect::<Vec<_>>();
+
+    let mut sequence = Vec::new();
+
+    let mut remaining = text.trim_start();
+    while let Some(next) = remaining.chars().next() {
+        let (context, length) = match next {
+            '#' => (SoundChangeContext::Boundary, 1),
+            'C' => (SoundChangeContext::Consonant(PhonemeSelector::blank()), 1),
+            'V' => (SoundChangeContext::Vowel(PhonemeSelector::blank()), 1),
+            '[' => match remaining.find(']') {
+                Some(end) => (parse_selector(&remaining[1..end])?, end + 1),
+                None => return Err(RuleError::Syntax(String::from("Unclosed selector")))
+            },
+            _ => {
+                let (id, symbol) = candidates
+                    .iter()
+                    .filter(|(_, symbol)| !symbol.is_empty() && remaining.starts_with(symbol.as_str()))
+                    .max_by_key(|(_, symbol)| symbol.len())
+                    .ok_or_else(|| RuleError::UnknownPhoneme(String::from(next)))?;
+
+                (SoundChangeContext::Phoneme(*id), symbol.len())
+            }
+        };
+
+        sequence.push(context);
+
+        remaining = remaining[length..].trim_start();
+    }
+
+    Ok(SoundChangeContext::from_sequence(sequence))
+}
+
+/// A source or destination written as a selector, such as `[Plosive Voiceless]`
+fn parse_shift(text: &str) -> Option<Result<SoundChangeContext, RuleError>> {
+    text.trim()
+        .strip_prefix('[')
+        .map(|text| match text.strip_suffix(']') {
+            Some(content) => parse_selector(content),
+            None => Err(RuleError::Syntax(String::from("Unclosed selector")))
+        })
+}
+
+/// A destination, along with the quality it's charted with in the dialect that the rule belongs to
+fn product(target: &Lineage<'_>, dst: DefaultKey) -> Result<(Phoneme, RawQuality), RuleError> {
+    let unknown = || RuleError::UnknownPhoneme(word_to_string(target.phonemes(), &[dst]));
+
+    let phoneme = target.phoneme(dst).ok_or_else(unknown)?;
+
+    let quality = if phoneme.phone.is_consonant() {
+        target.consonant_quality(dst).map(|quality| quality.into_raw())
+    } else if phoneme.phone.is_diphthong() {
+        // Diphthongs carry their own qualities
+        Some(PhonemeQuality::<Constriction, Place, Rounding>::blank().into_raw())
+    } else {
+        target.vowel_quality(dst).map(|quality| quality.into_raw())
+    };
+
+    quality.map(|quality| (phoneme.clone(), quality)).ok_or_else(unknown)
+}
+
+/// Reads a sequence of phonemes, where `∅` is the empty sequence
+fn parse_sequence(text: &str, lineage: &Lineage<'_>) -> Result<Word, RuleError> {
+    let text = text.trim();
+
+    if text == EMPTY { return Ok(Word::new()); }
+
+    match parse_word(text, lineage.candidates()) {
+        Some(word) if !word.is_empty() => Ok(word),
+        Some(..) => Err(RuleError::Syntax(format!("Expected a phoneme, or '{}' for nothing", EMPTY))),
+        None => Err(RuleError::UnknownPhoneme(String::from(text)))
+    }
+}
+
+/// Reads the operations written in place of a destination, such as `sk > Metathesis`
+fn parse_operation(src: &str, dst: &str, source: &Lineage<'_>) -> Option<Result<RuleChange, RuleError>> {
+    let operation = |src: &str| match dst.trim() {
+        "Metathesis" => parse_sequence(src, source).and_then(|src| match src.len() {
+            0 | 1 => Err(RuleError::Syntax(String::from("Metathesis needs at least two phonemes"))),
+            _ => Ok(RuleChange::Metathesis { src })
+        }),
+        "Gemination" => parse_phoneme(src, source).map(|src| RuleChange::Gemination { src }),
+        "Degemination" => parse_phoneme(src, source).map(|src| RuleChange::Degemination { src }),
+        _ => unreachable!()
+    };
+
+    matches!(dst.trim(), "Metathesis" | "Gemination" | "Degemination").then(|| operation(src))
+}
+
+/// Reads how sporadically a rule applies, written like `50%`, `50% per word` or `50% seed 7`
+fn parse_application(text: &str) -> Result<Application, RuleError> {
+    let mut words = text.split_whitespace();
+
+    let probability = words
+        .next()
+        .and_then(|word| word.strip_suffix('%'))
+        .and_then(|percent| percent.parse::<f32>().ok())
+        .filter(|percent| (0. ..=100.).contains(percent))
+        .ok_or_else(|| RuleError::Syntax(String::from("Expected a probability between 0% and 100% after '?'")))?;
+
+    let mut application = Application { probability: Some(probability / 100.), ..Default::default() };
+
+    while let Some(word) = words.next() {
+        match (word, words.next()) {
+            ("per", Some("word")) => application.diffusion = true,
+            ("seed", Some(seed)) => application.seed = seed
+                .parse()
+                .map_err(|_| RuleError::Syntax(format!("Invalid seed '{}'", seed)))?,
+            _ => return Err(RuleError::Syntax(format!("Unexpected '{}' after the probability", word)))
+        }
+    }
+
+    Ok(application)
+}
+
+/// Parses a rule of the form `src > dst / before_after`
+/// `src` and the environment are resolved against the parent's lineage,
+/// `dst` against the lineage of the dialect that the rule will belong to
+/// Environments are sequences of `C`, `V`, `#`, phonemes, 
+/// or selectors of categories and features such as `[Plosive Voiceless]` or `[+son -nasal]`
+/// `src` may require a stress (`ˈa`, `ˌa`, `a[Unstressed]`) or tone (`a˥˩`) of its syllable,
+/// which `dst` may change in turn
+/// Writing both `src` and `dst` as selectors shifts a whole class of phonemes at once, 
+/// such as `[Plosive Voiceless] > [Voiced]`
+/// Either side may be a sequence of phonemes or `∅`, such as `tj > tʃ`, `h > ∅` or `∅ > ə / C_C`,
+/// and `dst` may name an operation instead, such as `sk > Metathesis`, `t > Gemination` or `t > Degemination`
+/// A rule ending in `? 50%` is sporadic, see `parse_application`
+pub fn parse_rule(text: &str, source: &Lineage<'_>, target: &Lineage<'_>) -> Result<Rule, RuleError> {
+    let (text, application) = match text.split_once('?') {
+        Some((text, application)) => (text, parse_application(application)?),
+        None => (text, Application::default())
+    };
+
+    let (change, environment) = match text.split_once('/') {
+        Some((change, environment)) => (change, Some(environment)),
+        None => (text, None)
+    };
+
+    let (src, dst) = change
+        .split_once('>')
+        .ok_or_else(|| RuleError::Syntax(String::from("Expected '>' between source and destination")))?;
+
+    let context = match environment {
+        Some(environment) => {
+            let (before, after) = environment
+                .split_once('_')
+                .ok_or_else(|| RuleError::Syntax(String::from("Expected '_' in environment")))?;
+
+            (parse_environment(before, source)?, parse_environment(after, source)?)
+        },
+        None => (SoundChangeContext::Unrestricted, SoundChangeContext::Unrestricted)
+    };
+
+    match (parse_shift(src), parse_shift(dst)) {
+        (Some(src), Some(dst)) => {
+            let change = RuleChange::Shift { src: src?, dst: dst? };
+
+            return Ok(Rule { change, context, prosody: Default::default(), application });
+        },
+        (None, None) => { /*  */ },
+        _ => return Err(RuleError::Syntax(String::from(
+            "A selector can only shift into another selector, such as '[Plosive] > [Fricative]'")))
+    }
+
+    if let Some(change) = parse_operation(src, dst, source) {
+        return Ok(Rule { change: change?, context, prosody: Default::default(), application });
+    }
+
+    let (src, condition) = parse_suprasegmental(src)?;
+    let (dst, change) = parse_suprasegmental(dst)?;
+
+    let src = parse_sequence(src, source)?;
+
+    // Anything other than one phoneme becoming another rewrites a sequence
+    let (src, (dst, length)) = match (&src[..], parse_lengthened(dst, target)) {
+        ([src], Ok(dst)) => (*src, dst),
+        _ => {
+            let dst = parse_sequence(dst, target)?
+                .into_iter()
+                .map(|dst| product(target, dst))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if src.is_empty() && dst.is_empty() {
+                return Err(RuleError::Syntax(format!("'{0} > {0}' doesn't change anything", EMPTY)));
+            }
+
+            return Ok(Rule { 
+                change: RuleChange::Sequence { src, dst }, 
+                context, 
+                prosody: (condition, change), 
+                application 
+            });
+        }
+    };
+
+    let (mut phoneme, quality) = product(target, dst)?;
+
+    if let Some(length) = length {
+        phoneme.length = length;
+    }
+
+    Ok(Rule { 
+        change: RuleChange::Substitution { src, dst: phoneme, quality }, 
+        context, 
+        prosody: (condition, change),
+        application
+    })
+}
+
+/// The rules and stages of a chronology, as read from text
+pub type Chronology = (Vec<Rule>, Vec<Stage>);
+
+/// Parses one rule per line, skipping blank lines
+/// A line ending in `:`, such as `Old:`, begins a stage that the rules after it belong to
+/// Errors are paired with the (1-indexed) line they occurred on
+pub fn parse_rules(
+    text: &str, 
+    source: &Lineage<'_>, 
+    target: &Lineage<'_>) -> Result<Chronology, Vec<(usize, RuleError)>> {
+
+    let mut rules = Vec::new();
+    let mut stages = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() { continue; }
+
+        if let Some(name) = line.trim().strip_suffix(':') {
+            stages.push(Stage { name: String::from(name.trim()), start: rules.len() });
+            continue;
+        }
+
+        match parse_rule(line, source, target) {
+            Ok(rule) => rules.push(rule),
+            Err(error) => errors.push((idx + 1, error))
+        }
+    }
+
+    if errors.is_empty() { Ok((rules, stages)) } else { Err(errors) }
+}
+
+/// Writes a sequence of phonemes, or `∅` if it's empty
+/// Phonemes that were removed are written as `?`
+pub fn print_sequence(phonemes: &SlotMap<DefaultKey, Phoneme>, sequence: &[DefaultKey]) -> String {
+    match sequence {
+        [] => String::from(EMPTY),
+        sequence => word_to_string(phonemes, sequence)
+    }
+}
+
+/// The inverse of `parse_rule`
+pub fn print_rule(phonemes: &SlotMap<DefaultKey, Phoneme>, sound_change: &SoundChange) -> String {
+    let SoundChange { change: sound, prosody: (condition, change), application, .. } = sound_change;
+
+    let rule = match sound {
+        Change::Substitution { src, dst } => format!("{} > {}", 
+            condition.write(&print_sequence(phonemes, &[*src])), 
+            change.write(&print_sequence(phonemes, &[*dst]))),
+        Change::Shift { src, dst, .. } => format!("{} > {}", src.write(phonemes), dst.write(phonemes)),
+        Change::Sequence { src, dst } => format!("{} > {}", 
+            condition.write(&print_sequence(phonemes, src)), 
+            change.write(&print_sequence(phonemes, dst))),
+        Change::Metathesis { src } => format!("{} > Metathesis", print_sequence(phonemes, src)),
+        Change::Gemination { src } => format!("{} > Gemination", print_sequence(phonemes, &[*src])),
+        Change::Degemination { src } => format!("{} > Degemination", print_sequence(phonemes, &[*src]))
+    };
+
+    let rule = match sound_change.environment(phonemes) {
+        environment if environment.is_empty() => rule,
+        environment => format!("{} / {}", rule, environment)
+    };
+
+    // Exceptions are lexicon entries, which can't be written as text
+    match application {
+        Application { probability: None, .. } => rule,
+        Application { seed: 0, .. } => format!("{} ? {}", rule, application),
+        Application { seed, .. } => format!("{} ? {} seed {}", rule, application, seed)
+    }
+}
+
+/// The inverse of `parse_rules`
+pub fn print_rules(phonemes: &SlotMap<DefaultKey, Phoneme>, dialect: &Language) -> String {
+    dialect
+        .chronology()
+        .into_iter()
+        .map(|period| match period {
+            Period::Stage(stage) => format!("{}:", dialect.stages[stage].name),
+            Period::SoundChange(idx) => print_rule(phonemes, &dialect.sound_changes[idx])
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use crate::types::{Diacritic, Prosody, parse_word};
+
+    #[test]
+    fn rules_are_printed_as_they_were_written() {
+        let mut state = State::default();
+
+        let text = [
+            "p > f / V_V", 
+            "a > e / _k", 
+            "ˈa > e", 
+            "h > ∅ / _#", 
+            "sk > Metathesis", 
+            "Later:", 
+            "t > Gemination / [+son]_", 
+            "k > g / #s_ ? 50% seed 7"
+        ].join("\n");
+
+        let (rules, stages) = parse_rules(&text, &state.lineage(state.root), &state.lineage(state.root)).unwrap();
+        state.replace_sound_changes(state.root, rules, stages);
+
+        assert_eq!(print_rules(&state.phonemes, &state.dialects[state.language_tree[state.root]]), text);
+    }
+
+    #[test]
+    fn phonemes_written_in_environments_match_only_themselves() {
+        let mut state = State::default();
+
+        // An unreleased k has the same quality as k, but it isn't the phoneme the rule was written with
+        let k = parse_word("k", state.lineage(state.root).candidates()).unwrap()[0];
+        let quality = state.lineage(state.root).consonant_quality(k).unwrap();
+
+        let unreleased = {
+            let mut phoneme = state.phonemes[k].clone();
+            let _ = phoneme.diacritics.insert(Diacritic::NoAudibleRelease);
+
+            let id = state.phonemes.insert(phoneme);
+            state.phonemes[id].set_id(id);
+            id
+        };
+
+        state.dialects[state.language_tree[state.root]].consonants.add_phoneme(unreleased, quality);
+
+        let lineage = state.lineage(state.root);
+
+        let rule = parse_rule("a > e / _k", &lineage, &lineage).unwrap();
+        assert!(rule.context.1 == SoundChangeContext::Phoneme(k));
+
+        let sound_change = SoundChange {
+            change: Change::Substitution { 
+                src: parse_phoneme("a", &lineage).unwrap(), 
+                dst: parse_phoneme("e", &lineage).unwrap() 
+            },
+            context: rule.context,
+            prosody: rule.prosody,
+            application: rule.application
+        };
+
+        assert_eq!(print_rule(&state.phonemes, &sound_change), "a > e / _k");
+
+        let apply = |text: &str| {
+            let mut word = parse_word(text, lineage.candidates()).unwrap();
+            sound_change.apply(&lineage, &mut word, &mut Prosody::default(), None);
+
+            word_to_string(&state.phonemes, &word)
+        };
+
+        assert_eq!(apply("ak"), "ek");
+        assert_eq!(apply("ak̚"), "ak̚");
+    }
+}
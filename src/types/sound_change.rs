@@ -1,19 +1,944 @@
-use slotmap::DefaultKey;
+use std::collections::HashSet;
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
 
-use crate::types::PhonemeSelector;
+use enum_iterator::all;
+use slotmap::{DefaultKey, SlotMap, Key};
+
+use crate::types::{PhonemeSelector, PhonemeQuality, Phoneme, Language, Word, Alphabet, RawQuality, word_to_string};
+use crate::types::{Prosody, Suprasegmentals, Tone, Features, EntryKey, Random, is_nucleus};
+use crate::types::category::{Category, Outer, Inner, Pair};
 use crate::types::category::{Articulation, Region, Voicing, Constriction, Place, Rounding};
 
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
 #[derive(serde::Deserialize, serde::Serialize)]
 pub enum SoundChangeContext {
     Consonant(PhonemeSelector<Articulation, Region, Voicing>),
     Vowel(PhonemeSelector<Constriction, Place, Rounding>),
+    /// Any segment with the given features, whether it's a consonant or a vowel
+    Natural(Features),
+    /// A phoneme written literally, which only matches segments that sound the same
+    Phoneme(DefaultKey),
     Multiple(Vec<SoundChangeContext>),
+    Boundary,
+    #[default]
     Unrestricted
 }
 
+impl SoundChangeContext {
+    /// Collapses a sequence of contexts into the simplest equivalent context
+    pub fn from_sequence(mut sequence: Vec<SoundChangeContext>) -> Self {
+        match sequence.len() {
+            0 => SoundChangeContext::Unrestricted,
+            1 => sequence.pop().unwrap(),
+            _ => SoundChangeContext::Multiple(sequence)
+        }
+    }
+
+    /// The inverse of `from_sequence`
+    pub fn to_sequence(&self) -> Vec<SoundChangeContext> {
+        match self {
+            SoundChangeContext::Multiple(sequence) => sequence.clone(),
+            SoundChangeContext::Unrestricted => Vec::new(),
+            context => vec![context.clone()]
+        }
+    }
+
+    fn matches_segment(&self, lineage: &Lineage<'_>, segment: DefaultKey) -> bool {
+        match self {
+            SoundChangeContext::Consonant(selector) => lineage
+                .consonant_quality(segment)
+                .zip(lineage.features(segment))
+                .map_or(false, |(quality, features)| coarticulated(selector).meets(&quality, &features)),
+            SoundChangeContext::Vowel(selector) => {
+                let features = match lineage.features(segment) {
+                    Some(features) => features,
+                    None => return false
+                };
+
+                match lineage.phoneme(segment).and_then(|phoneme| phoneme.phone.glide()) {
+                    // A diphthong is a single vowel that meets a selector if either of its ends does
+                    Some(glide) => glide
+                        .into_iter()
+                        .any(|quality| selector.meets(&quality, &features)),
+                    None => lineage
+                        .vowel_quality(segment)
+                        .map_or(false, |quality| selector.meets(&quality, &features))
+                }
+            },
+            SoundChangeContext::Natural(selector) => lineage
+                .features(segment)
+                .map_or(false, |features| features.meets(selector)),
+            SoundChangeContext::Phoneme(id) => lineage.is_same_sound(segment, *id),
+            _ => unreachable!()
+        }
+    }
+
+    /// True if the context is a single selector that `segment` meets
+    pub fn selects(&self, lineage: &Lineage<'_>, segment: DefaultKey) -> bool {
+        match self {
+            SoundChangeContext::Consonant(..) //
+                | SoundChangeContext::Vowel(..) //
+                | SoundChangeContext::Natural(..) //
+                | SoundChangeContext::Phoneme(..) => self.matches_segment(lineage, segment),
+            _ => false
+        }
+    }
+
+    /// The phonemes written literally in the context
+    pub fn phonemes(&self) -> Vec<DefaultKey> {
+        match self {
+            SoundChangeContext::Phoneme(id) => vec![*id],
+            SoundChangeContext::Multiple(sequence) => sequence
+                .iter()
+                .flat_map(|context| context.phonemes())
+                .collect(),
+            _ => Vec::new()
+        }
+    }
+
+    /// Replaces every phoneme written in the context with the one `map` gives for it
+    pub fn remap(&mut self, map: &impl Fn(DefaultKey) -> DefaultKey) {
+        match self {
+            SoundChangeContext::Phoneme(id) => *id = map(*id),
+            SoundChangeContext::Multiple(sequence) => sequence
+                .iter_mut()
+                .for_each(|context| context.remap(map)),
+            _ => { /*  */ }
+        }
+    }
+
+    /// The context in the notation rules are written in
+    pub fn write(&self, phonemes: &SlotMap<DefaultKey, Phoneme>) -> String {
+        match self {
+            SoundChangeContext::Consonant(selector) => write_selector(selector, "C"),
+            SoundChangeContext::Vowel(selector) => write_selector(selector, "V"),
+            SoundChangeContext::Natural(features) => format!("[{}]", features),
+            SoundChangeContext::Phoneme(id) => word_to_string(phonemes, &[*id]),
+            SoundChangeContext::Multiple(sequence) => sequence
+                .iter()
+                .map(|context| context.write(phonemes))
+                .collect(),
+            SoundChangeContext::Boundary => String::from("#"),
+            SoundChangeContext::Unrestricted => String::new()
+        }
+    }
+
+    /// Matches the context against the segments that begin at `idx`
+    /// Returns the index just past the matched segments
+    pub fn matches_after(&self, lineage: &Lineage<'_>, word: &[DefaultKey], idx: usize) -> Option<usize> {
+        match self {
+            SoundChangeContext::Unrestricted => Some(idx),
+            SoundChangeContext::Boundary => (idx == word.len()).then_some(idx),
+            SoundChangeContext::Multiple(sequence) => sequence
+                .iter()
+                .try_fold(idx, |idx, context| context.matches_after(lineage, word, idx)),
+            context => word
+                .get(idx)
+                .filter(|segment| context.matches_segment(lineage, **segment))
+                .map(|_| idx + 1)
+        }
+    }
+
+    /// Matches the context against the segments that end just before `idx`
+    /// Returns the index of the first matched segment
+    pub fn matches_before(&self, lineage: &Lineage<'_>, word: &[DefaultKey], idx: usize) -> Option<usize> {
+        match self {
+            SoundChangeContext::Unrestricted => Some(idx),
+            SoundChangeContext::Boundary => (idx == 0).then_some(idx),
+            SoundChangeContext::Multiple(sequence) => sequence
+                .iter()
+                .rev()
+                .try_fold(idx, |idx, context| context.matches_before(lineage, word, idx)),
+            context => idx
+                .checked_sub(1)
+                .filter(|idx| context.matches_segment(lineage, word[*idx]))
+        }
+    }
+}
+
+/// A place also selects the co-articulated places made with it, so that `[Velar]` matches w alongside k
+fn coarticulated(selector: &PhonemeSelector<Articulation, Region, Voicing>) -> PhonemeSelector<Articulation, Region, Voicing> {
+    let PhonemeQuality(a, b, c) = &selector.quality;
+
+    let places = b
+        .iter()
+        .copied()
+        .chain(all::<Region>()
+            .filter(|region| region.is_coarticulated() && !b.contains(region))
+            .filter(|region| region.places().iter().any(|place| b.contains(place))))
+        .collect::<Vec<_>>();
+
+    PhonemeSelector { 
+        quality: PhonemeQuality(a.clone(), Rc::from(places), c.clone()), 
+        features: selector.features.clone() 
+    }
+}
+
+fn write_selector<A, B, C>(selector: &PhonemeSelector<A, B, C>, blank: &str) -> String 
+    where A: Category, B: Category, C: Category {
+
+    let PhonemeSelector { quality: PhonemeQuality(a, b, c), features } = selector;
+
+    let categories = a.iter().map(|a| format!("{:?}", a))
+        .chain(b.iter().map(|b| format!("{:?}", b)))
+        .chain(c.iter().map(|c| format!("{:?}", c)))
+        .chain((!features.is_empty()).then(|| format!("{}", features)))
+        .collect::<Vec<_>>();
+
+    if categories.is_empty() {
+        String::from(blank)
+    } else {
+        format!("[{}]", categories.join(" "))
+    }
+}
+
+/// What a sound change turns the segments it targets into
+#[derive(Clone)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum Change {
+    /// Replaces every segment that sounds like `src` with `dst`
+    Substitution { src: DefaultKey, dst: DefaultKey },
+    /// Moves every phoneme that meets `src` to the cell of the IPA 
+    /// with the categories and features of `dst`, such as `[Plosive Voiceless] > [Voiced]`
+    /// Each phoneme is paired with the product it was resolved to when the change was added
+    Shift { src: SoundChangeContext, dst: SoundChangeContext, products: Vec<(DefaultKey, DefaultKey)> },
+    /// Replaces a sequence of segments with another, such as `tj > tʃ`
+    /// An empty `src` inserts `dst` wherever the environment matches, and an empty `dst` deletes `src`
+    Sequence { src: Vec<DefaultKey>, dst: Vec<DefaultKey> },
+    /// Reverses the order of a sequence of segments, such as `sk > ks`
+    Metathesis { src: Vec<DefaultKey> },
+    /// Doubles every segment that sounds like `src`
+    Gemination { src: DefaultKey },
+    /// Reduces every doubled `src` to a single segment
+    Degemination { src: DefaultKey }
+}
+
+impl Change {
+    fn matches(lineage: &Lineage<'_>, word: &[DefaultKey], idx: usize, src: &[DefaultKey]) -> bool {
+        word.len() >= idx + src.len() //
+            && src
+                .iter()
+                .zip(&word[idx..])
+                .all(|(src, segment)| lineage.is_same_sound(*segment, *src))
+    }
+
+    /// The segments that the change targets at `idx`, and what they turn into
+    /// Returns the number of segments targeted alongside their replacement
+    pub fn rewrite(&self, lineage: &Lineage<'_>, word: &[DefaultKey], idx: usize) -> Option<(usize, Word)> {
+        match self {
+            Change::Substitution { src, dst } => Change::matches(lineage, word, idx, &[*src])
+                .then(|| (1, vec![*dst])),
+            Change::Shift { products, .. } => word
+                .get(idx)
+                .and_then(|segment| products.iter().find(|(src, _)| lineage.is_same_sound(*segment, *src)))
+                .map(|(_, dst)| (1, vec![*dst])),
+            Change::Sequence { src, dst } => Change::matches(lineage, word, idx, src)
+                .then(|| (src.len(), dst.clone())),
+            Change::Metathesis { src } => Change::matches(lineage, word, idx, src)
+                .then(|| (src.len(), word[idx..idx + src.len()].iter().rev().copied().collect())),
+            Change::Gemination { src } => Change::matches(lineage, word, idx, &[*src])
+                .then(|| (1, vec![word[idx]; 2])),
+            Change::Degemination { src } => Change::matches(lineage, word, idx, &[*src, *src])
+                .then(|| (2, vec![word[idx]]))
+        }
+    }
+
+    /// Replaces every phoneme the change refers to with the one `map` gives for it
+    pub fn remap(&mut self, map: impl Fn(DefaultKey) -> DefaultKey) {
+        let remap = |id: &mut DefaultKey| *id = map(*id);
+
+        match self {
+            Change::Substitution { src, dst } => {
+                remap(src);
+                remap(dst);
+            },
+            Change::Shift { products, .. } => products
+                .iter_mut()
+                .for_each(|(src, dst)| {
+                    remap(src);
+                    remap(dst);
+                }),
+            Change::Sequence { src, dst } => src.iter_mut().chain(dst.iter_mut()).for_each(remap),
+            Change::Metathesis { src } => src.iter_mut().for_each(remap),
+            Change::Gemination { src } | Change::Degemination { src } => remap(src)
+        }
+    }
+
+    /// Every phoneme that the change refers to, its products included
+    pub fn phonemes(&self) -> Vec<DefaultKey> {
+        match self {
+            Change::Substitution { src, dst } => vec![*src, *dst],
+            Change::Shift { products, .. } => products
+                .iter()
+                .flat_map(|(src, dst)| [*src, *dst])
+                .collect(),
+            Change::Sequence { src, dst } => src.iter().chain(dst.iter()).copied().collect(),
+            Change::Metathesis { src } => src.clone(),
+            Change::Gemination { src } | Change::Degemination { src } => vec![*src]
+        }
+    }
+
+    /// Every phoneme that the change owns
+    pub fn products(&self) -> Vec<DefaultKey> {
+        match self {
+            Change::Substitution { dst, .. } => vec![*dst],
+            Change::Shift { products, .. } => products.iter().map(|(_, dst)| *dst).collect(),
+            Change::Sequence { dst, .. } => dst.clone(),
+            Change::Metathesis { .. } | Change::Gemination { .. } | Change::Degemination { .. } => Vec::new()
+        }
+    }
+}
+
+/// How regularly a sound change applies across the lexicon
+#[derive(Clone, PartialEq, Default, Debug)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Application {
+    /// The chance that the change reaches each site it targets, or every site if `None`
+    pub probability: Option<f32>,
+    pub seed: u64,
+    /// Rolls the chance once per word instead of once per site,
+    /// so that the change reaches either all of a word or none of it
+    pub diffusion: bool,
+    /// Entries of the root language's lexicon that the change never reaches
+    pub exceptions: Vec<EntryKey>
+}
+
+impl Application {
+    /// Rolls are seeded by the word they're made for, 
+    /// so that a word always changes the same way
+    fn random(&self, word: &[DefaultKey]) -> Random {
+        let seed = word
+            .iter()
+            .fold(self.seed, |seed, segment| Random::new(seed ^ segment.data().as_ffi()).next_u64());
+
+        Random::new(seed)
+    }
+}
+
+impl fmt::Display for Application {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let probability = match self.probability {
+            Some(probability) => probability,
+            None => return write!(f, "Regular")
+        };
+
+        write!(f, "{}%", (probability * 1e4).round() / 1e2)?;
+
+        if self.diffusion {
+            write!(f, " per word")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct SoundChange {
-    pub src: DefaultKey,
-    pub dst: DefaultKey,
-    pub context: (SoundChangeContext, SoundChangeContext)
-}
\ No newline at end of file
+    pub change: Change,
+    pub context: (SoundChangeContext, SoundChangeContext),
+    /// The stress and tone required of the source's syllable, 
+    /// then given to the destination's
+    #[serde(default)]
+    pub prosody: (Suprasegmentals, Suprasegmentals),
+    #[serde(default)]
+    pub application: Application
+}
+
+impl SoundChange {
+    /// Rewrites every sequence of `word` that the change targets,
+    /// so long as the segments around it satisfy both contexts
+    /// Environments are checked against the word as it was before the change,
+    /// so every matching sequence changes simultaneously
+    /// Syllables keep their stress and tone unless the change gives them new ones,
+    /// passing from a rewritten nucleus to the first nucleus of its replacement, 
+    /// and are forgotten once their nucleus stops being a vowel
+    /// Sporadic changes skip some of those sequences, and never touch the words of their exceptions
+    /// Returns true if the change fired at least once
+    pub fn apply(
+        &self, 
+        lineage: &Lineage<'_>, 
+        word: &mut Word, 
+        prosody: &mut Prosody, 
+        entry: Option<EntryKey>) -> bool {
+
+        let Application { probability, diffusion, exceptions, .. } = &self.application;
+
+        if entry.map_or(false, |entry| exceptions.contains(&entry)) {
+            return false;
+        }
+
+        let mut random = self.application.random(word);
+
+        let probability = match probability {
+            Some(probability) if *diffusion => {
+                if random.next_f32() >= *probability { return false; }
+
+                None
+            },
+            probability => *probability
+        };
+
+        let original = mem::take(word);
+        let syllables = mem::take(&mut prosody.0);
+
+        let (before, after) = &self.context;
+        let (condition, change) = &self.prosody;
+
+        // The replacements made, as the range of the original they replaced 
+        // and the index in the rewritten word where they begin
+        let mut fired = Vec::new();
+
+        let mut idx = 0;
+        while idx <= original.len() {
+            let syllable = Prosody::nucleus(lineage, &original, idx)
+                .and_then(|nucleus| syllables.get(&nucleus).cloned())
+                .unwrap_or_default();
+
+            let rewrite = self.change
+                .rewrite(lineage, &original, idx)
+                .filter(|(length, _)| {
+                    condition.matches(&syllable) //
+                        && before.matches_before(lineage, &original, idx).is_some() //
+                        && after.matches_after(lineage, &original, idx + length).is_some() //
+                        && probability.map_or(true, |probability| random.next_f32() < probability)
+                });
+
+            let length = match rewrite {
+                Some((length, replacement)) => {
+                    fired.push((idx..idx + length, word.len(), replacement.len()));
+
+                    word.extend(replacement);
+
+                    length
+                },
+                None => 0
+            };
+
+            // Insertions are followed by the segment they were inserted before
+            if length == 0 {
+                if let Some(segment) = original.get(idx) {
+                    prosody.0.extend(syllables.get(&idx).map(|syllable| (word.len(), syllable.clone())));
+
+                    word.push(*segment);
+                }
+
+                idx += 1;
+            } else {
+                idx += length;
+            }
+        }
+
+        for (range, start, length) in fired.iter() {
+            let nucleus = (*start..start + length)
+                .find(|idx| lineage.phoneme(word[*idx]).map_or(false, is_nucleus));
+
+            let nucleus = match nucleus {
+                Some(nucleus) => nucleus,
+                None => continue
+            };
+
+            let mut syllable = range
+                .clone()
+                .find_map(|idx| syllables.get(&idx).cloned())
+                .unwrap_or_default();
+
+            change.apply(&mut syllable);
+
+            prosody.set_syllable(nucleus, syllable);
+        }
+
+        prosody.retain_nuclei(lineage.phonemes(), word);
+
+        !fired.is_empty()
+    }
+
+    /// The environment in the conventional `before_after` notation
+    /// Empty if the change is unconditioned
+    pub fn environment(&self, phonemes: &SlotMap<DefaultKey, Phoneme>) -> String {
+        match &self.context {
+            (SoundChangeContext::Unrestricted, SoundChangeContext::Unrestricted) => String::new(),
+            (before, after) => format!("{}_{}", before.write(phonemes), after.write(phonemes))
+        }
+    }
+}
+
+fn replace_axes<A, B, C>(quality: PhonemeQuality<A, B, C>, categories: &PhonemeQuality<A, B, C>) -> PhonemeQuality<A, B, C> 
+    where A: Category, B: Category, C: Category {
+
+    let PhonemeQuality(a, b, c) = quality;
+    let PhonemeQuality(x, y, z) = categories;
+
+    PhonemeQuality(
+        if x.is_empty() { a } else { x.clone() },
+        if y.is_empty() { b } else { y.clone() },
+        if z.is_empty() { c } else { z.clone() }
+    )
+}
+
+/// Of the cells of `alphabet` that meet `restriction`, finds the one whose product
+/// has every feature in `required` and shares the most features with `target`
+/// Ties go to the product with the lowest symbol, so that a shift always resolves the same way
+fn closest_cell<A, B, C>(
+    alphabet: &Alphabet<A, B, C>,
+    restriction: PhonemeQuality<A, B, C>,
+    product: impl Fn(DefaultKey) -> Option<Phoneme>,
+    features: impl Fn(&Phoneme, PhonemeQuality<A, B, C>) -> Features,
+    target: &Features,
+    required: &Features) -> Option<(Phoneme, RawQuality)> where A: Outer<B, C>, B: Inner<C>, C: Pair {
+
+    alphabet
+        .select_phonemes(restriction)
+        .filter_map(|cell| {
+            let product = product(cell)?;
+            let quality = alphabet.get_quality(cell)?;
+            let features = features(&product, quality.clone());
+
+            let distance = target.0
+                .iter()
+                .filter(|(feature, value)| features.get(**feature) != Some(**value))
+                .count();
+
+            features
+                .meets(required)
+                .then(|| ((distance, format!("{}", product)), product, quality))
+        })
+        .min_by(|(a, ..), (b, ..)| a.cmp(b))
+        .map(|(_, product, quality)| (product, quality.into_raw()))
+}
+
+/// Finds what the phoneme at `id` becomes under a shift to `dst`, as a cell of the IPA's charts
+/// Categories written in `dst` replace the phoneme's own, and its features take on those of `dst`
+/// The product keeps the phoneme's length and any diacritics that don't alter its quality
+/// Returns `None` if no cell of the IPA fits
+pub fn resolve_shift(
+    lineage: &Lineage<'_>, 
+    ipa: &Language, 
+    id: DefaultKey, 
+    dst: &SoundChangeContext) -> Option<(Phoneme, RawQuality)> {
+
+    let phoneme = lineage.phoneme(id)?;
+
+    let product = |cell: DefaultKey| {
+        let mut product = lineage.phoneme(cell)?.clone();
+
+        product.diacritics = phoneme.diacritics
+            .iter()
+            .filter(|diacritic| !diacritic.modifies_quality())
+            .copied()
+            .collect();
+
+        product.length = phoneme.length;
+        Some(product)
+    };
+
+    let required = match dst {
+        SoundChangeContext::Consonant(PhonemeSelector { features, .. }) //
+            | SoundChangeContext::Vowel(PhonemeSelector { features, .. }) //
+            | SoundChangeContext::Natural(features) => features.clone(),
+        _ => return None
+    };
+
+    let mut target = lineage.features(id)?;
+    for (feature, value) in required.0.iter() {
+        target.set(*feature, *value);
+    }
+
+    if let Some(quality) = lineage.consonant_quality(id) {
+        let restriction = match dst {
+            SoundChangeContext::Consonant(selector) if selector.quality != PhonemeQuality::blank() => 
+                replace_axes(quality, &selector.quality),
+            SoundChangeContext::Consonant(..) | SoundChangeContext::Natural(..) => PhonemeQuality::blank(),
+            _ => return None
+        };
+
+        closest_cell(&ipa.consonants, restriction, product, Phoneme::consonant_features, &target, &required)
+    } else if let Some(quality) = lineage.vowel_quality(id) {
+        let restriction = match dst {
+            SoundChangeContext::Vowel(selector) if selector.quality != PhonemeQuality::blank() => 
+                replace_axes(quality, &selector.quality),
+            SoundChangeContext::Vowel(..) | SoundChangeContext::Natural(..) => PhonemeQuality::blank(),
+            _ => return None
+        };
+
+        closest_cell(&ipa.vowels, restriction, product, Phoneme::vowel_features, &target, &required)
+    } else {
+        None
+    }
+}
+
+/// The chain of languages a word may have inherited its phonemes from
+/// A dialect only owns the phonemes its sound changes produced,
+/// so the qualities of any other segment must be found in one of its ancestors
+pub struct Lineage<'a> {
+    phonemes: &'a SlotMap<DefaultKey, Phoneme>,
+    languages: Vec<&'a Language>
+}
+
+impl<'a> Lineage<'a> {
+    pub fn new(phonemes: &'a SlotMap<DefaultKey, Phoneme>) -> Self {
+        Self { phonemes, languages: Vec::new() }
+    }
+
+    /// Languages should be pushed from the most distant ancestor to the most recent
+    pub fn push(&mut self, language: &'a Language) {
+        self.languages.push(language);
+    }
+
+    /// `None` if the phoneme was removed
+    pub fn phoneme(&self, id: DefaultKey) -> Option<&'a Phoneme> {
+        self.phonemes.get(id)
+    }
+
+    pub fn phonemes(&self) -> &'a SlotMap<DefaultKey, Phoneme> {
+        self.phonemes
+    }
+
+    /// Every phoneme in the lineage, starting with the most recent language
+    pub fn candidates(&self) -> impl Iterator<Item = (DefaultKey, String)> + '_ {
+        self.languages
+            .iter()
+            .rev()
+            .flat_map(|language| language.phonemes())
+            .filter_map(|id| self.phonemes
+                .get(id)
+                .map(|phoneme| (id, format!("{}", phoneme))))
+    }
+
+    /// Every distinct sound in the lineage, ordered by symbol
+    /// A sound charted more than once is given by the most recent language's phoneme
+    pub fn sounds(&self) -> Vec<(String, DefaultKey)> {
+        let mut seen = HashSet::new();
+
+        let mut sounds = self.candidates()
+            .filter(|(_, symbol)| seen.insert(symbol.clone()))
+            .map(|(id, symbol)| (symbol, id))
+            .collect::<Vec<_>>();
+
+        sounds.sort();
+        sounds
+    }
+
+    /// Every tone in the lineage, starting with the most recent language
+    pub fn tones(&self) -> impl Iterator<Item = &'a Tone> + '_ {
+        self.languages
+            .iter()
+            .rev()
+            .flat_map(|language| language.tones.iter())
+    }
+
+    pub fn consonant_quality(&self, id: DefaultKey) -> Option<PhonemeQuality<Articulation, Region, Voicing>> {
+        self.languages
+            .iter()
+            .rev()
+            .find_map(|language| language.consonants.get_quality(id))
+            .zip(self.phonemes.get(id))
+            .map(|(quality, phoneme)| phoneme.consonant_quality(quality))
+    }
+
+    pub fn vowel_quality(&self, id: DefaultKey) -> Option<PhonemeQuality<Constriction, Place, Rounding>> {
+        self.languages
+            .iter()
+            .rev()
+            .find_map(|language| language.vowels.get_quality(id))
+            .zip(self.phonemes.get(id))
+            .map(|(quality, phoneme)| phoneme.vowel_quality(quality))
+    }
+
+    /// The distinctive features of a segment, as charted by the nearest language that has it
+    pub fn features(&self, id: DefaultKey) -> Option<Features> {
+        let phoneme = self.phonemes.get(id)?;
+
+        if phoneme.phone.is_diphthong() {
+            return Some(phoneme.vowel_features(PhonemeQuality::blank()));
+        }
+
+        let consonant = self.languages
+            .iter()
+            .rev()
+            .find_map(|language| language.consonants.get_quality(id))
+            .map(|quality| phoneme.consonant_features(quality));
+
+        consonant.or_else(|| self.languages
+            .iter()
+            .rev()
+            .find_map(|language| language.vowels.get_quality(id))
+            .map(|quality| phoneme.vowel_features(quality)))
+    }
+
+    /// Two segments sound the same if they share a key
+    /// or if they are rendered identically
+    pub fn is_same_sound(&self, a: DefaultKey, b: DefaultKey) -> bool {
+        if a == b { return true; }
+
+        match (self.phonemes.get(a), self.phonemes.get(b)) {
+            (Some(a), Some(b)) => a.phone == b.phone && format!("{}", a) == format!("{}", b),
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use crate::types::{parse_word, word_to_string, Lexicon, Entry};
+
+    /// Reads `text` as a word written with the IPA's symbols
+    fn word(lineage: &Lineage<'_>, text: &str) -> Word {
+        parse_word(text, lineage.candidates()).unwrap()
+    }
+
+    fn substitution(lineage: &Lineage<'_>, src: &str, dst: &str) -> SoundChange {
+        SoundChange {
+            change: Change::Substitution { src: word(lineage, src)[0], dst: word(lineage, dst)[0] },
+            context: Default::default(),
+            prosody: Default::default(),
+            application: Default::default()
+        }
+    }
+
+    fn change(change: Change) -> SoundChange {
+        SoundChange { change, context: Default::default(), prosody: Default::default(), application: Default::default() }
+    }
+
+    /// The word that `sound_change` makes of `text`, and whether it fired
+    fn apply(lineage: &Lineage<'_>, sound_change: &SoundChange, text: &str) -> (String, bool) {
+        let mut word = word(lineage, text);
+        let fired = sound_change.apply(lineage, &mut word, &mut Prosody::default(), None);
+
+        (word_to_string(lineage.phonemes(), &word), fired)
+    }
+
+    #[test]
+    fn substitutions_rewrite_every_occurrence() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let sound_change = substitution(&lineage, "p", "f");
+
+        assert_eq!(apply(&lineage, &sound_change, "papap"), (String::from("fafaf"), true));
+    }
+
+    #[test]
+    fn words_without_the_source_are_unchanged() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let sound_change = substitution(&lineage, "p", "f");
+
+        assert_eq!(apply(&lineage, &sound_change, "tata"), (String::from("tata"), false));
+        assert_eq!(apply(&lineage, &sound_change, ""), (String::new(), false));
+    }
+
+    #[test]
+    fn environments_are_read_before_the_change() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let p = lineage.consonant_quality(word(&lineage, "p")[0]).unwrap();
+
+        // Were each rewrite seen by the next, the second /f/ would keep the third /p/ from changing
+        let mut sound_change = substitution(&lineage, "p", "f");
+        sound_change.context.0 = SoundChangeContext::Consonant(p.into());
+
+        assert_eq!(apply(&lineage, &sound_change, "ppp").0, "pff");
+    }
+
+    #[test]
+    fn boundaries_match_the_edges_of_the_word() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let mut initial = substitution(&lineage, "p", "f");
+        initial.context.0 = SoundChangeContext::Boundary;
+
+        let mut r#final = substitution(&lineage, "p", "f");
+        r#final.context.1 = SoundChangeContext::Boundary;
+
+        assert_eq!(apply(&lineage, &initial, "papap").0, "fapap");
+        assert_eq!(apply(&lineage, &r#final, "papap").0, "papaf");
+        assert_eq!(apply(&lineage, &initial, "p").0, "f");
+    }
+
+    #[test]
+    fn selectors_match_segments_of_their_kind() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let word = word(&lineage, "pat");
+
+        let consonant = SoundChangeContext::Consonant(PhonemeSelector::blank());
+        let vowel = SoundChangeContext::Vowel(PhonemeSelector::blank());
+
+        assert_eq!(consonant.matches_after(&lineage, &word, 0), Some(1));
+        assert_eq!(consonant.matches_after(&lineage, &word, 1), None);
+        assert_eq!(vowel.matches_after(&lineage, &word, 1), Some(2));
+        assert_eq!(vowel.matches_before(&lineage, &word, 2), Some(1));
+        assert_eq!(vowel.matches_before(&lineage, &word, 1), None);
+
+        let voiceless = lineage.consonant_quality(word[0]).unwrap();
+        let voiceless = SoundChangeContext::Consonant(PhonemeQuality(
+            Rc::from(&[][..]), 
+            Rc::from(&[][..]), 
+            voiceless.2
+        ).into());
+
+        assert_eq!(voiceless.matches_before(&lineage, &word, 1), Some(0));
+        assert_eq!(voiceless.matches_after(&lineage, &word, 2), Some(3));
+    }
+
+    #[test]
+    fn features_match_consonants_and_vowels_alike() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let word = word(&lineage, "mas");
+
+        let (feature, value) = Features::parse_value("+son").unwrap();
+        let mut sonorant = Features::default();
+        sonorant.set(feature, value);
+
+        let sonorant = SoundChangeContext::Natural(sonorant);
+
+        assert_eq!(sonorant.matches_after(&lineage, &word, 0), Some(1));
+        assert_eq!(sonorant.matches_after(&lineage, &word, 1), Some(2));
+        assert_eq!(sonorant.matches_after(&lineage, &word, 2), None);
+    }
+
+    #[test]
+    fn sequences_match_outward_from_the_target() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let word = word(&lineage, "pata");
+
+        let sequence = SoundChangeContext::from_sequence(vec![
+            SoundChangeContext::Consonant(PhonemeSelector::blank()),
+            SoundChangeContext::Vowel(PhonemeSelector::blank())
+        ]);
+
+        assert_eq!(sequence.matches_after(&lineage, &word, 0), Some(2));
+        assert_eq!(sequence.matches_after(&lineage, &word, 1), None);
+        assert_eq!(sequence.matches_before(&lineage, &word, 4), Some(2));
+        assert_eq!(sequence.matches_before(&lineage, &word, 3), None);
+    }
+
+    #[test]
+    fn environments_stop_at_the_edges_of_the_word() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let word = word(&lineage, "pa");
+
+        let boundary = SoundChangeContext::Boundary;
+        let consonant = SoundChangeContext::Consonant(PhonemeSelector::blank());
+
+        assert_eq!(boundary.matches_before(&lineage, &word, 0), Some(0));
+        assert_eq!(boundary.matches_before(&lineage, &word, 1), None);
+        assert_eq!(boundary.matches_after(&lineage, &word, 2), Some(2));
+        assert_eq!(boundary.matches_after(&lineage, &word, 1), None);
+
+        assert_eq!(consonant.matches_before(&lineage, &word, 0), None);
+        assert_eq!(consonant.matches_after(&lineage, &word, 2), None);
+
+        assert_eq!(SoundChangeContext::Unrestricted.matches_after(&lineage, &word, 2), Some(2));
+    }
+
+    #[test]
+    fn sporadic_changes_are_the_same_for_a_seed() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let sporadic = |seed: u64, diffusion: bool| {
+            let mut sound_change = substitution(&lineage, "p", "f");
+            sound_change.application = Application { probability: Some(0.5), seed, diffusion, ..Default::default() };
+
+            sound_change
+        };
+
+        let (changed, fired) = apply(&lineage, &sporadic(7, false), "papapapapapapapa");
+        assert!(fired);
+        assert!(changed.contains('p') && changed.contains('f'));
+
+        assert_eq!(apply(&lineage, &sporadic(7, false), "papapapapapapapa").0, changed);
+        assert_ne!(apply(&lineage, &sporadic(8, false), "papapapapapapapa").0, changed);
+
+        // Changes that diffuse reach either every site of a word or none of them
+        for seed in 0..8 {
+            let (changed, fired) = apply(&lineage, &sporadic(seed, true), "papapapa");
+
+            assert_eq!(changed, if fired { "fafafafa" } else { "papapapa" });
+        }
+    }
+
+    #[test]
+    fn exceptions_are_left_unchanged() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let mut lexicon = Lexicon::with_key();
+        let exception = lexicon.insert(Entry::default());
+        let entry = lexicon.insert(Entry::default());
+
+        let mut sound_change = substitution(&lineage, "p", "f");
+        sound_change.application.exceptions.push(exception);
+
+        let apply = |entry: Option<EntryKey>| {
+            let mut word = word(&lineage, "pa");
+            let fired = sound_change.apply(&lineage, &mut word, &mut Prosody::default(), entry);
+
+            (word_to_string(lineage.phonemes(), &word), fired)
+        };
+
+        assert_eq!(apply(Some(exception)), (String::from("pa"), false));
+        assert_eq!(apply(Some(entry)), (String::from("fa"), true));
+        assert_eq!(apply(None), (String::from("fa"), true));
+    }
+
+    #[test]
+    fn sequences_replace_inserts_and_deletes() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let replacement = change(Change::Sequence { src: word(&lineage, "tj"), dst: word(&lineage, "tʃ") });
+        assert_eq!(apply(&lineage, &replacement, "atja"), (String::from("atʃa"), true));
+        assert_eq!(apply(&lineage, &replacement, "atia"), (String::from("atia"), false));
+
+        let mut insertion = change(Change::Sequence { src: Vec::new(), dst: word(&lineage, "ə") });
+        insertion.context.0 = SoundChangeContext::Boundary;
+        assert_eq!(apply(&lineage, &insertion, "pa"), (String::from("əpa"), true));
+
+        let deletion = change(Change::Sequence { src: word(&lineage, "h"), dst: Vec::new() });
+        assert_eq!(apply(&lineage, &deletion, "haha"), (String::from("aa"), true));
+    }
+
+    #[test]
+    fn metathesis_reverses_the_sequence() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let metathesis = change(Change::Metathesis { src: word(&lineage, "sk") });
+
+        assert_eq!(apply(&lineage, &metathesis, "askaska"), (String::from("aksaksa"), true));
+        assert_eq!(apply(&lineage, &metathesis, "aksa"), (String::from("aksa"), false));
+    }
+
+    #[test]
+    fn gemination_doubles_and_degemination_halves() {
+        let state = State::default();
+        let lineage = state.lineage(state.root);
+
+        let t = word(&lineage, "t")[0];
+
+        let gemination = change(Change::Gemination { src: t });
+        assert_eq!(apply(&lineage, &gemination, "ata"), (String::from("atta"), true));
+
+        let degemination = change(Change::Degemination { src: t });
+        assert_eq!(apply(&lineage, &degemination, "atta"), (String::from("ata"), true));
+        assert_eq!(apply(&lineage, &degemination, "attta"), (String::from("atta"), true));
+        assert_eq!(apply(&lineage, &degemination, "ata"), (String::from("ata"), false));
+    }
+}
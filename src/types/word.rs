@@ -0,0 +1,59 @@
+use slotmap::{DefaultKey, SlotMap};
+
+use crate::types::Phoneme;
+
+/// A word is a sequence of phoneme keys into `State::phonemes`
+pub type Word = Vec<DefaultKey>;
+
+pub fn word_to_string(
+    phonemes: &SlotMap<DefaultKey, Phoneme>, 
+    word: &[DefaultKey]) -> String {
+
+    word.iter().fold(String::new(), |mut content, id| {
+        match phonemes.get(*id) {
+            Some(phoneme) => content.push_str(format!("{}", phoneme).as_str()),
+            None => content.push('?')
+        }
+
+        content
+    })
+}
+
+/// False if any of the word's phonemes were removed
+pub fn is_valid_word(phonemes: &SlotMap<DefaultKey, Phoneme>, word: &[DefaultKey]) -> bool {
+    word.iter().all(|id| phonemes.contains_key(*id))
+}
+
+/// Splits `text` into phonemes by repeatedly taking the longest matching symbol
+/// When two candidates share a symbol, the one that appears first wins
+/// Returns `None` if some part of `text` can't be matched
+pub fn parse_word(
+    text: &str, 
+    candidates: impl IntoIterator<Item = (DefaultKey, String)>) -> Option<Word> {
+
+    let candidates = candidates
+        .into_iter()
+        .filter(|(_, symbol)| !symbol.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut word = Word::new();
+
+    let mut remaining = text.trim();
+    while !remaining.is_empty() {
+        let (id, symbol) = candidates
+            .iter()
+            .filter(|(_, symbol)| remaining.starts_with(symbol.as_str()))
+            .fold(None, |longest: Option<&(DefaultKey, String)>, candidate| {
+                match longest {
+                    Some(longest) if longest.1.len() >= candidate.1.len() => Some(longest),
+                    _ => Some(candidate)
+                }
+            })?;
+
+        word.push(*id);
+
+        remaining = &remaining[symbol.len()..];
+    }
+
+    Some(word)
+}